@@ -0,0 +1,267 @@
+use super::*;
+
+const ACHIEVEMENTS_KEY: &str = "achievements.txt";
+const TOAST_SECS: f32 = 3.0;
+const TOAST_WIDTH: f32 = 560.0;
+const TOAST_HEIGHT: f32 = 110.0;
+
+/// What has to happen for an achievement to unlock.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Condition {
+    /// Clear this many stages in one run.
+    StagesCleared(usize),
+    /// Clear a stage without shooting a single stinger.
+    StageWithoutFiring,
+    /// Clear this many stages in a row without losing a hive.
+    HivesSavedStreak(usize),
+    /// Clear a stage without the bee or any hive taking damage.
+    FlawlessStage,
+    Kills(u32),
+    KillsOf(EnemyKind, u32),
+    Combo(u32),
+    Score(u64),
+}
+
+#[derive(Debug)]
+pub struct Achievement {
+    /// Stable, saved with the unlocks.
+    pub id: &'static str,
+    pub name: &'static str,
+    pub description: &'static str,
+    pub condition: Condition,
+}
+
+pub const ACHIEVEMENTS: &[Achievement] = &[
+    Achievement {
+        id: "first_sting",
+        name: "First Sting",
+        description: "Kill a hornet",
+        condition: Condition::Kills(1),
+    },
+    Achievement {
+        id: "stage_5",
+        name: "Holding the Line",
+        description: "Clear stage 5",
+        condition: Condition::StagesCleared(5),
+    },
+    Achievement {
+        id: "stage_10",
+        name: "Queen's Guard",
+        description: "Clear stage 10",
+        condition: Condition::StagesCleared(10),
+    },
+    Achievement {
+        id: "pacifist",
+        name: "Conscientious Objector",
+        description: "Clear a stage without firing",
+        condition: Condition::StageWithoutFiring,
+    },
+    Achievement {
+        id: "beekeeper",
+        name: "Beekeeper",
+        description: "Save every hive for five stages in a row",
+        condition: Condition::HivesSavedStreak(5),
+    },
+    Achievement {
+        id: "flawless",
+        name: "Untouchable",
+        description: "Clear a stage without you or a hive taking damage",
+        condition: Condition::FlawlessStage,
+    },
+    Achievement {
+        id: "kills_100",
+        name: "Exterminator",
+        description: "Kill 100 hornets in one run",
+        condition: Condition::Kills(100),
+    },
+    Achievement {
+        id: "brutes_10",
+        name: "Giant Slayer",
+        description: "Kill 10 brutes in one run",
+        condition: Condition::KillsOf(EnemyKind::Brute, 10),
+    },
+    Achievement {
+        id: "combo_20",
+        name: "Swarm Breaker",
+        description: "Reach a 20 kill combo",
+        condition: Condition::Combo(20),
+    },
+    Achievement {
+        id: "score_50k",
+        name: "Honey Money",
+        description: "Score 50,000 points in one run",
+        condition: Condition::Score(50_000),
+    },
+];
+
+/// Per-stage bookkeeping the run stats don't cover.
+#[derive(Debug, Default)]
+struct Progress {
+    shots_this_stage: u32,
+    damaged_this_stage: bool,
+    hive_lost_this_stage: bool,
+    saved_streak: usize,
+    cleared_without_firing: bool,
+    cleared_flawless: bool,
+}
+
+#[derive(Debug, Default)]
+pub struct Achievements {
+    /// Indices into `ACHIEVEMENTS`.
+    unlocked: Vec<usize>,
+    progress: Progress,
+    /// Newly unlocked and waiting to be shown, oldest first.
+    toasts: Vec<usize>,
+    /// How long the first toast has been up.
+    toast_age: f32,
+}
+
+impl Achievements {
+    /// Unlocks are saved as one id per line.
+    pub fn load() -> Self {
+        let text = storage::load(ACHIEVEMENTS_KEY).unwrap_or_default();
+        let unlocked = text
+            .lines()
+            .filter_map(|id| ACHIEVEMENTS.iter().position(|a| a.id == id.trim()))
+            .collect();
+
+        Achievements {
+            unlocked,
+            ..Default::default()
+        }
+    }
+
+    fn save(&self) {
+        let text: String = self
+            .unlocked
+            .iter()
+            .map(|&i| format!("{}\n", ACHIEVEMENTS[i].id))
+            .collect();
+        storage::save(ACHIEVEMENTS_KEY, &text);
+    }
+
+    pub fn is_unlocked(&self, index: usize) -> bool {
+        self.unlocked.contains(&index)
+    }
+
+    pub fn handle(&mut self, event: &GameEvent) {
+        let progress = &mut self.progress;
+        match *event {
+            GameEvent::StageStarted { stage } => {
+                if stage == 0 {
+                    *progress = Progress::default();
+                }
+                progress.shots_this_stage = 0;
+                progress.damaged_this_stage = false;
+                progress.hive_lost_this_stage = false;
+            }
+            GameEvent::Shot { .. } => progress.shots_this_stage += 1,
+            GameEvent::HiveDamaged { .. } | GameEvent::PlayerDamaged { .. } => {
+                progress.damaged_this_stage = true
+            }
+            GameEvent::HiveDestroyed { .. } => progress.hive_lost_this_stage = true,
+            GameEvent::StageCleared { .. } => {
+                if progress.hive_lost_this_stage {
+                    progress.saved_streak = 0;
+                } else {
+                    progress.saved_streak += 1;
+                }
+                progress.cleared_without_firing |= progress.shots_this_stage == 0;
+                progress.cleared_flawless |= !progress.damaged_this_stage;
+            }
+            _ => {}
+        }
+    }
+
+    /// Unlocks anything newly earned, saving and queueing a toast for it.
+    pub fn check(&mut self, stats: &RunStats, score: &Score) {
+        let progress = &self.progress;
+        let met = |condition: Condition| match condition {
+            Condition::StagesCleared(stages) => stats.stages_cleared >= stages,
+            Condition::StageWithoutFiring => progress.cleared_without_firing,
+            Condition::HivesSavedStreak(stages) => progress.saved_streak >= stages,
+            Condition::FlawlessStage => progress.cleared_flawless,
+            Condition::Kills(kills) => stats.total_kills() >= kills,
+            Condition::KillsOf(kind, kills) => stats.kills_of(kind) >= kills,
+            Condition::Combo(combo) => score.best_combo >= combo,
+            Condition::Score(points) => score.total >= points,
+        };
+
+        let earned: Vec<_> = (0..ACHIEVEMENTS.len())
+            .filter(|&i| !self.unlocked.contains(&i) && met(ACHIEVEMENTS[i].condition))
+            .collect();
+        if earned.is_empty() {
+            return;
+        }
+
+        self.unlocked.extend(&earned);
+        self.toasts.extend(&earned);
+        self.save();
+    }
+
+    /// Advanced by real time, so toasts still go away on the menus.
+    pub fn tick(&mut self, dt: f32) {
+        if self.toasts.is_empty() {
+            return;
+        }
+
+        self.toast_age += dt;
+        if self.toast_age >= TOAST_SECS {
+            self.toasts.remove(0);
+            self.toast_age = 0.0;
+        }
+    }
+
+    /// The oldest unshown unlock, sliding in from the top right of the HUD.
+    pub fn draw_toast(&self) {
+        let achievement = match self.toasts.first() {
+            Some(&i) => &ACHIEVEMENTS[i],
+            None => return,
+        };
+
+        let slide = (self.toast_age.min(TOAST_SECS - self.toast_age) / 0.25).min(1.0);
+        let x = VIRTUAL_WIDTH - 20.0 - TOAST_WIDTH * slide;
+        let y = 20.0;
+        draw_rectangle(
+            x,
+            y,
+            TOAST_WIDTH,
+            TOAST_HEIGHT,
+            Color::new(0.1, 0.1, 0.1, 0.9),
+        );
+        draw_rectangle_lines(x, y, TOAST_WIDTH, TOAST_HEIGHT, 3.0, GOLD);
+        draw_scaled_text("Achievement unlocked!", x + 20.0, y + 40.0, 34, GOLD);
+        draw_scaled_text(achievement.name, x + 20.0, y + 90.0, 44, WHITE);
+    }
+
+    pub fn draw_gallery(&self) {
+        draw_centered_text(
+            &format!(
+                "Achievements ({}/{})",
+                self.unlocked.len(),
+                ACHIEVEMENTS.len()
+            ),
+            VIRTUAL_WIDTH / 2.0,
+            150.0,
+            80,
+            WHITE,
+        );
+
+        for (i, achievement) in ACHIEVEMENTS.iter().enumerate() {
+            let y = 260.0 + 80.0 * i as f32;
+            let (name_color, description_color) = if self.is_unlocked(i) {
+                (GOLD, LIGHTGRAY)
+            } else {
+                (DARKGRAY, GRAY)
+            };
+            draw_scaled_text(achievement.name, 250.0, y, 44, name_color);
+            draw_scaled_text(
+                achievement.description,
+                250.0,
+                y + 34.0,
+                30,
+                description_color,
+            );
+        }
+    }
+}
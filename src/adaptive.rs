@@ -0,0 +1,160 @@
+use super::*;
+
+use std::fmt::Write;
+
+const ADAPTIVE_LOG_KEY: &str = "adaptive_log.txt";
+/// Seconds of play between each look at how the player is doing.
+const WINDOW_SECS: f32 = 8.0;
+/// How far one adjustment moves either factor.
+const STEP: f32 = 0.05;
+const MIN_FACTOR: f32 = 0.7;
+const MAX_FACTOR: f32 = 1.3;
+/// Stings within one window, counting a hit on the bee as two, that are
+/// enough to ease off.
+const STRUGGLING_PRESSURE: u32 = 3;
+/// Kills per second, without taking a sting, that are enough to push harder.
+const CRUISING_KILL_RATE: f32 = 0.6;
+
+/// Optional dynamic difficulty: gently scales hornet speed and how many fly
+/// at once by how the current stage is going, starting each stage neutral.
+/// Every change is logged to `adaptive_log.txt` for designers to review.
+#[derive(Debug)]
+pub struct AdaptiveDifficulty {
+    pub enabled: bool,
+    speed: f32,
+    spawns: f32,
+    stage: usize,
+    /// Seconds of play this run.
+    run_time: f32,
+    window_time: f32,
+    hive_stings: u32,
+    player_hits: u32,
+    kills: u32,
+    log: String,
+}
+
+impl Default for AdaptiveDifficulty {
+    fn default() -> Self {
+        AdaptiveDifficulty {
+            enabled: false,
+            speed: 1.0,
+            spawns: 1.0,
+            stage: 0,
+            run_time: 0.0,
+            window_time: 0.0,
+            hive_stings: 0,
+            player_hits: 0,
+            kills: 0,
+            log: String::new(),
+        }
+    }
+}
+
+impl AdaptiveDifficulty {
+    pub fn new(enabled: bool) -> Self {
+        AdaptiveDifficulty {
+            enabled,
+            ..Default::default()
+        }
+    }
+
+    /// Scales how fast newly spawned hornets fly, or 1 when disabled.
+    pub fn speed_factor(&self) -> f32 {
+        if self.enabled {
+            self.speed
+        } else {
+            1.0
+        }
+    }
+
+    /// Scales how many hornets fly at once, or 1 when disabled.
+    pub fn spawn_factor(&self) -> f32 {
+        if self.enabled {
+            self.spawns
+        } else {
+            1.0
+        }
+    }
+
+    pub fn handle(&mut self, event: &GameEvent) {
+        match *event {
+            GameEvent::StageStarted { stage } => {
+                if stage == 0 {
+                    self.run_time = 0.0;
+                    self.log.clear();
+                }
+                self.stage = stage;
+                self.speed = 1.0;
+                self.spawns = 1.0;
+                self.start_window();
+                if self.enabled {
+                    self.log_line(&format!(
+                        "{:7.1}s stage {:2} started",
+                        self.run_time,
+                        stage + 1
+                    ));
+                }
+            }
+            GameEvent::HiveDamaged { .. } => self.hive_stings += 1,
+            GameEvent::PlayerDamaged { .. } => self.player_hits += 1,
+            GameEvent::EnemyKilled { .. } => self.kills += 1,
+            _ => {}
+        }
+    }
+
+    fn start_window(&mut self) {
+        self.window_time = 0.0;
+        self.hive_stings = 0;
+        self.player_hits = 0;
+        self.kills = 0;
+    }
+
+    /// Advanced by play time only, so pauses don't count as a quiet spell.
+    pub fn tick(&mut self, dt: f32) {
+        self.run_time += dt;
+        self.window_time += dt;
+        if !self.enabled || self.window_time < WINDOW_SECS {
+            return;
+        }
+
+        let pressure = self.hive_stings + self.player_hits * 2;
+        let kill_rate = self.kills as f32 / self.window_time;
+        let (delta, reason) = if pressure >= STRUGGLING_PRESSURE {
+            (-STEP, "struggling")
+        } else if pressure == 0 && kill_rate >= CRUISING_KILL_RATE {
+            (STEP, "cruising")
+        } else {
+            (0.0, "")
+        };
+
+        let speed = (self.speed + delta).clamp(MIN_FACTOR, MAX_FACTOR);
+        let spawns = (self.spawns + delta).clamp(MIN_FACTOR, MAX_FACTOR);
+        if speed != self.speed || spawns != self.spawns {
+            self.speed = speed;
+            self.spawns = spawns;
+            let line = format!(
+                "{:7.1}s stage {:2}: {} ({} hive stings, {} hits, {:.2} kills/s) -> speed x{:.2}, spawns x{:.2}",
+                self.run_time,
+                self.stage + 1,
+                reason,
+                self.hive_stings,
+                self.player_hits,
+                kill_rate,
+                self.speed,
+                self.spawns,
+            );
+            self.log_line(&line);
+        }
+        self.start_window();
+    }
+
+    /// Saves the whole run's log each time, so it's there however the run
+    /// ends.
+    fn log_line(&mut self, line: &str) {
+        info!("adaptive difficulty: {}", line);
+
+        // writing to a String can't fail
+        let _ = writeln!(self.log, "{}", line);
+        storage::save(ADAPTIVE_LOG_KEY, &self.log);
+    }
+}
@@ -0,0 +1,111 @@
+/// A named sequence of evenly timed frames.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Clip {
+    /// Used to find the clip's frames in the sprite atlas.
+    pub name: &'static str,
+    pub frames: usize,
+    pub frame_secs: f32,
+    pub looping: bool,
+}
+
+pub const IDLE: Clip = Clip {
+    name: "idle",
+    frames: 1,
+    frame_secs: 1.0,
+    looping: true,
+};
+
+pub const WING_FLAP: Clip = Clip {
+    name: "flap",
+    frames: 4,
+    frame_secs: 0.03,
+    looping: true,
+};
+
+pub const ATTACK_WINDUP: Clip = Clip {
+    name: "windup",
+    frames: 4,
+    frame_secs: 0.05,
+    looping: true,
+};
+
+pub const DAMAGE_WOBBLE: Clip = Clip {
+    name: "wobble",
+    frames: 6,
+    frame_secs: 0.05,
+    looping: false,
+};
+
+pub const DEATH: Clip = Clip {
+    name: "death",
+    frames: 6,
+    frame_secs: 0.06,
+    looping: false,
+};
+
+/// Plays a clip, advanced by simulation time so it stops while the game is
+/// paused or frozen.
+#[derive(Debug, Clone, Copy)]
+pub struct Animation {
+    clip: Clip,
+    time: f32,
+}
+
+impl Animation {
+    pub fn new(clip: Clip) -> Self {
+        Animation { clip, time: 0.0 }
+    }
+
+    pub fn clip(&self) -> Clip {
+        self.clip
+    }
+
+    /// Switches to `clip`, leaving it alone if it's already playing.
+    pub fn play(&mut self, clip: Clip) {
+        if self.clip != clip {
+            self.restart(clip);
+        }
+    }
+
+    pub fn restart(&mut self, clip: Clip) {
+        self.clip = clip;
+        self.time = 0.0;
+    }
+
+    pub fn tick(&mut self, dt: f32) {
+        self.time += dt;
+    }
+
+    pub fn frame(&self) -> usize {
+        let frame = (self.time / self.clip.frame_secs) as usize;
+        if self.clip.looping {
+            frame % self.clip.frames
+        } else {
+            frame.min(self.clip.frames - 1)
+        }
+    }
+
+    pub fn finished(&self) -> bool {
+        !self.clip.looping && self.time >= self.clip.frame_secs * self.clip.frames as f32
+    }
+
+    /// How far through the clip it is, 0 to 1.
+    pub fn progress(&self) -> f32 {
+        (self.time / (self.clip.frame_secs * self.clip.frames as f32)).min(1.0)
+    }
+}
+
+/// How open a flapping wing is, for procedural drawing.
+pub fn wing_scale(animation: &Animation) -> f32 {
+    if animation.clip() != WING_FLAP {
+        return 1.0;
+    }
+
+    [1.0, 0.8, 0.55, 0.8][animation.frame()]
+}
+
+impl Default for Animation {
+    fn default() -> Self {
+        Animation::new(IDLE)
+    }
+}
@@ -0,0 +1,199 @@
+use super::*;
+
+use std::collections::HashMap;
+
+/// Hornets further away than this can't be heard.
+const HEARING_DISTANCE: f32 = 1200.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SoundEffect {
+    Shoot,
+    Hit,
+    Kill,
+    HiveDamage,
+    PlayerDamage,
+    StageClear,
+    GameOver,
+}
+
+impl SoundEffect {
+    pub const ALL: [SoundEffect; 7] = [
+        SoundEffect::Shoot,
+        SoundEffect::Hit,
+        SoundEffect::Kill,
+        SoundEffect::HiveDamage,
+        SoundEffect::PlayerDamage,
+        SoundEffect::StageClear,
+        SoundEffect::GameOver,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            SoundEffect::Shoot => "shoot",
+            SoundEffect::Hit => "hit",
+            SoundEffect::Kill => "kill",
+            SoundEffect::HiveDamage => "hive_damage",
+            SoundEffect::PlayerDamage => "player_damage",
+            SoundEffect::StageClear => "stage_clear",
+            SoundEffect::GameOver => "game_over",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MusicTrack {
+    Title,
+    Stage,
+    Victory,
+    Defeat,
+}
+
+impl MusicTrack {
+    pub const ALL: [MusicTrack; 4] = [
+        MusicTrack::Title,
+        MusicTrack::Stage,
+        MusicTrack::Victory,
+        MusicTrack::Defeat,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            MusicTrack::Title => "title",
+            MusicTrack::Stage => "stage",
+            MusicTrack::Victory => "victory",
+            MusicTrack::Defeat => "defeat",
+        }
+    }
+}
+
+/// Volumes from 0 to 1; music and effects are both scaled by master.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Mixer {
+    pub master: f32,
+    pub music: f32,
+    pub sfx: f32,
+}
+
+impl Mixer {
+    pub fn music_volume(&self) -> f32 {
+        self.master * self.music
+    }
+
+    pub fn sfx_volume(&self) -> f32 {
+        self.master * self.sfx
+    }
+}
+
+impl Default for Mixer {
+    fn default() -> Self {
+        Mixer {
+            master: 0.8,
+            music: 0.6,
+            sfx: 1.0,
+        }
+    }
+}
+
+/// Sound effects from `assets/sfx/<name>.wav`, synthesized when there's no
+/// file, and music from `assets/music/<name>.ogg`, silent when missing.
+#[derive(Debug, Default)]
+pub struct Audio {
+    effects: HashMap<SoundEffect, Sound>,
+    music: HashMap<MusicTrack, Sound>,
+    playing: Option<MusicTrack>,
+    /// Looping hornet buzz, louder the closer the nearest hornet is.
+    drone: Option<Sound>,
+    mixer: Mixer,
+}
+
+impl Audio {
+    pub async fn load() -> Self {
+        let mut audio = Audio::default();
+
+        for effect in SoundEffect::ALL.iter().copied() {
+            let sound = match load_sound(&format!("assets/sfx/{}.wav", effect.name())).await {
+                Ok(sound) => Ok(sound),
+                Err(_) => load_synthesized(&synth::preset(effect)).await,
+            };
+            if let Ok(sound) = sound {
+                audio.effects.insert(effect, sound);
+            }
+        }
+
+        if let Ok(sound) = load_synthesized(&synth::hornet_drone()).await {
+            play_sound(
+                sound,
+                PlaySoundParams {
+                    looped: true,
+                    volume: 0.0,
+                },
+            );
+            audio.drone = Some(sound);
+        }
+
+        for track in MusicTrack::ALL.iter().copied() {
+            if let Ok(sound) = load_sound(&format!("assets/music/{}.ogg", track.name())).await {
+                audio.music.insert(track, sound);
+            }
+        }
+
+        audio
+    }
+
+    pub fn play(&self, effect: SoundEffect) {
+        if let Some(sound) = self.effects.get(&effect) {
+            play_sound(
+                *sound,
+                PlaySoundParams {
+                    looped: false,
+                    volume: self.mixer.sfx_volume(),
+                },
+            );
+        }
+    }
+
+    /// Switches the background music, leaving it alone if it's already playing.
+    pub fn play_music(&mut self, track: MusicTrack) {
+        if self.playing == Some(track) {
+            return;
+        }
+
+        if let Some(sound) = self.playing.and_then(|playing| self.music.get(&playing)) {
+            stop_sound(*sound);
+        }
+
+        if let Some(sound) = self.music.get(&track) {
+            play_sound(
+                *sound,
+                PlaySoundParams {
+                    looped: true,
+                    volume: self.mixer.music_volume(),
+                },
+            );
+        }
+
+        self.playing = Some(track);
+    }
+
+    /// Sets the buzz volume from the distance to the nearest hornet, if any.
+    pub fn set_drone_distance(&self, distance: Option<f32>) {
+        if let Some(drone) = self.drone {
+            let proximity = distance
+                .map(|d| (1.0 - d / HEARING_DISTANCE).max(0.0))
+                .unwrap_or(0.0);
+            set_sound_volume(drone, proximity * proximity * self.mixer.sfx_volume());
+        }
+    }
+
+    pub fn set_mixer(&mut self, mixer: Mixer) {
+        self.mixer = mixer;
+
+        if let Some(sound) = self.playing.and_then(|playing| self.music.get(&playing)) {
+            set_sound_volume(*sound, self.mixer.music_volume());
+        }
+    }
+}
+
+async fn load_synthesized(params: &SynthParams) -> Result<Sound, FileError> {
+    load_sound_from_bytes(&synth::to_wav(&synth::render(params))).await
+}
@@ -0,0 +1,73 @@
+use super::*;
+
+/// How quickly the camera catches up to its target, higher is snappier.
+const DEFAULT_LAG: f32 = 6.0;
+
+#[derive(Debug, Clone, Copy)]
+pub struct FollowCamera {
+    /// Center of the view, in world coordinates.
+    pub center: Vec2,
+    pub lag: f32,
+    /// The camera never shows anything outside of these world coordinates.
+    pub bounds: Rect,
+}
+
+impl FollowCamera {
+    pub fn new(bounds: Rect) -> Self {
+        FollowCamera {
+            center: bounds.center(),
+            lag: DEFAULT_LAG,
+            bounds,
+        }
+    }
+
+    pub fn view_size(&self) -> Vec2 {
+        Vec2::new(VIRTUAL_WIDTH, VIRTUAL_HEIGHT)
+    }
+
+    pub fn view_rect(&self) -> Rect {
+        let size = self.view_size();
+        Rect::new(
+            self.center.x - size.x / 2.0,
+            self.center.y - size.y / 2.0,
+            size.x,
+            size.y,
+        )
+    }
+
+    pub fn snap_to(&mut self, target: Vec2) {
+        self.center = target;
+        self.constrain();
+    }
+
+    /// Eases towards the target, framerate independently.
+    pub fn follow(&mut self, target: Vec2) {
+        let t = 1.0 - (-self.lag * get_frame_time()).exp();
+        self.center += (target - self.center) * t;
+        self.constrain();
+    }
+
+    pub fn camera(&self) -> Camera2D {
+        view_camera(self.view_rect())
+    }
+
+    fn constrain(&mut self) {
+        let half = self.view_size() / 2.0;
+
+        self.center.x = if self.bounds.w <= half.x * 2.0 {
+            self.bounds.center().x
+        } else {
+            self.center
+                .x
+                .clamp(self.bounds.left() + half.x, self.bounds.right() - half.x)
+        };
+
+        self.center.y = if self.bounds.h <= half.y * 2.0 {
+            self.bounds.center().y
+        } else {
+            self.center
+                .y
+                .clamp(self.bounds.top() + half.y, self.bounds.bottom() - half.y)
+        };
+    }
+}
@@ -0,0 +1,247 @@
+use super::*;
+
+/// Everything the keyboard does during play.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    MoveUp,
+    MoveDown,
+    MoveLeft,
+    MoveRight,
+    ShootUp,
+    ShootDown,
+    ShootLeft,
+    ShootRight,
+    Pause,
+    Minimap,
+    /// Spends honey on a new hive, in endless mode or between campaign stages.
+    BuildHive,
+}
+
+impl Action {
+    pub const ALL: [Action; 11] = [
+        Action::MoveUp,
+        Action::MoveDown,
+        Action::MoveLeft,
+        Action::MoveRight,
+        Action::ShootUp,
+        Action::ShootDown,
+        Action::ShootLeft,
+        Action::ShootRight,
+        Action::Pause,
+        Action::Minimap,
+        Action::BuildHive,
+    ];
+
+    /// What each bee has its own key for in co-op; the rest are shared and
+    /// only player one's keys do them.
+    pub const PER_PLAYER: [Action; 8] = [
+        Action::MoveUp,
+        Action::MoveDown,
+        Action::MoveLeft,
+        Action::MoveRight,
+        Action::ShootUp,
+        Action::ShootDown,
+        Action::ShootLeft,
+        Action::ShootRight,
+    ];
+
+    /// The actions the bee at index `player` has keys for.
+    pub fn for_player(player: usize) -> &'static [Action] {
+        if player == 0 {
+            &Action::ALL
+        } else {
+            &Action::PER_PLAYER
+        }
+    }
+
+    /// Used as the key in the settings file.
+    pub fn name(self) -> &'static str {
+        match self {
+            Action::MoveUp => "move_up",
+            Action::MoveDown => "move_down",
+            Action::MoveLeft => "move_left",
+            Action::MoveRight => "move_right",
+            Action::ShootUp => "shoot_up",
+            Action::ShootDown => "shoot_down",
+            Action::ShootLeft => "shoot_left",
+            Action::ShootRight => "shoot_right",
+            Action::Pause => "pause",
+            Action::Minimap => "minimap",
+            Action::BuildHive => "build_hive",
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Action::MoveUp => "Move up",
+            Action::MoveDown => "Move down",
+            Action::MoveLeft => "Move left",
+            Action::MoveRight => "Move right",
+            Action::ShootUp => "Shoot up",
+            Action::ShootDown => "Shoot down",
+            Action::ShootLeft => "Shoot left",
+            Action::ShootRight => "Shoot right",
+            Action::Pause => "Pause",
+            Action::Minimap => "Minimap",
+            Action::BuildHive => "Build hive",
+        }
+    }
+}
+
+/// Keys that can be bound, which is also how their names are looked up when
+/// reading the settings file back.
+const BINDABLE_KEYS: &[KeyCode] = &[
+    KeyCode::A,
+    KeyCode::B,
+    KeyCode::C,
+    KeyCode::D,
+    KeyCode::E,
+    KeyCode::F,
+    KeyCode::G,
+    KeyCode::H,
+    KeyCode::I,
+    KeyCode::J,
+    KeyCode::K,
+    KeyCode::L,
+    KeyCode::M,
+    KeyCode::N,
+    KeyCode::O,
+    KeyCode::P,
+    KeyCode::Q,
+    KeyCode::R,
+    KeyCode::S,
+    KeyCode::T,
+    KeyCode::U,
+    KeyCode::V,
+    KeyCode::W,
+    KeyCode::X,
+    KeyCode::Y,
+    KeyCode::Z,
+    KeyCode::Key0,
+    KeyCode::Key1,
+    KeyCode::Key2,
+    KeyCode::Key3,
+    KeyCode::Key4,
+    KeyCode::Key5,
+    KeyCode::Key6,
+    KeyCode::Key7,
+    KeyCode::Key8,
+    KeyCode::Key9,
+    KeyCode::Up,
+    KeyCode::Down,
+    KeyCode::Left,
+    KeyCode::Right,
+    KeyCode::Space,
+    KeyCode::Enter,
+    KeyCode::Tab,
+    KeyCode::Escape,
+    KeyCode::LeftShift,
+    KeyCode::RightShift,
+    KeyCode::LeftControl,
+    KeyCode::RightControl,
+    KeyCode::LeftAlt,
+    KeyCode::RightAlt,
+    KeyCode::Comma,
+    KeyCode::Period,
+    KeyCode::Slash,
+    KeyCode::Semicolon,
+    KeyCode::Apostrophe,
+    KeyCode::LeftBracket,
+    KeyCode::RightBracket,
+    KeyCode::Kp0,
+    KeyCode::Kp1,
+    KeyCode::Kp2,
+    KeyCode::Kp3,
+    KeyCode::Kp4,
+    KeyCode::Kp5,
+    KeyCode::Kp6,
+    KeyCode::Kp7,
+    KeyCode::Kp8,
+    KeyCode::Kp9,
+];
+
+pub fn key_name(key: KeyCode) -> String {
+    format!("{:?}", key)
+}
+
+pub fn key_from_name(name: &str) -> Option<KeyCode> {
+    BINDABLE_KEYS
+        .iter()
+        .copied()
+        .find(|&key| key_name(key) == name)
+}
+
+pub fn is_bindable(key: KeyCode) -> bool {
+    BINDABLE_KEYS.contains(&key)
+}
+
+/// One key per action, indexed in `Action::ALL` order.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Controls {
+    keys: [KeyCode; 11],
+}
+
+impl Default for Controls {
+    fn default() -> Self {
+        Controls {
+            keys: [
+                KeyCode::W,
+                KeyCode::S,
+                KeyCode::A,
+                KeyCode::D,
+                KeyCode::Up,
+                KeyCode::Down,
+                KeyCode::Left,
+                KeyCode::Right,
+                KeyCode::Escape,
+                KeyCode::M,
+                KeyCode::H,
+            ],
+        }
+    }
+}
+
+impl Controls {
+    /// The defaults for the bee at index `player`. The second bee's are clear
+    /// of the first's: IJKL to move and the number pad to shoot.
+    pub fn defaults_for(player: usize) -> Self {
+        let mut controls = Controls::default();
+        if player == 0 {
+            return controls;
+        }
+        let keys = [
+            KeyCode::I,
+            KeyCode::K,
+            KeyCode::J,
+            KeyCode::L,
+            KeyCode::Kp8,
+            KeyCode::Kp5,
+            KeyCode::Kp4,
+            KeyCode::Kp6,
+        ];
+        for (&action, &key) in Action::PER_PLAYER.iter().zip(&keys) {
+            controls.bind(action, key);
+        }
+        controls
+    }
+
+    fn index(action: Action) -> usize {
+        Action::ALL.iter().position(|&a| a == action).unwrap()
+    }
+
+    pub fn key(&self, action: Action) -> KeyCode {
+        self.keys[Controls::index(action)]
+    }
+
+    pub fn bind(&mut self, action: Action, key: KeyCode) {
+        self.keys[Controls::index(action)] = key;
+    }
+
+    pub fn is_down(&self, action: Action) -> bool {
+        is_key_down(self.key(action))
+    }
+
+    pub fn is_pressed(&self, action: Action) -> bool {
+        is_key_pressed(self.key(action))
+    }
+}
@@ -0,0 +1,92 @@
+use super::*;
+
+const DAILY_KEY: &str = "daily.txt";
+
+/// Today's UTC date as `YYYY-MM-DD`; everyone playing on the same day gets the
+/// same challenge.
+pub fn today() -> String {
+    date_string(date::now())
+}
+
+/// The seed shared by everyone playing `date`'s challenge: an FNV-1a hash of
+/// the date.
+pub fn daily_seed(date: &str) -> u64 {
+    date.bytes().fold(0xCBF2_9CE4_8422_2325, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x0100_0000_01B3)
+    })
+}
+
+/// The one attempt allowed at the daily challenge, recorded as soon as it
+/// starts so quitting out doesn't earn a second go.
+#[derive(Debug, Default)]
+pub struct DailyChallenge {
+    /// `YYYY-MM-DD` of the last attempt.
+    date: String,
+    score: u64,
+    /// Counted from 1.
+    stage: usize,
+    finished: bool,
+}
+
+impl DailyChallenge {
+    /// Saved as one `date score stage finished` line.
+    pub fn load() -> Self {
+        let text = storage::load(DAILY_KEY).unwrap_or_default();
+        let mut fields = text.split_whitespace();
+        let mut parse = || -> Option<DailyChallenge> {
+            Some(DailyChallenge {
+                date: fields.next()?.to_string(),
+                score: fields.next()?.parse().ok()?,
+                stage: fields.next()?.parse().ok()?,
+                finished: fields.next()?.parse().ok()?,
+            })
+        };
+
+        parse().unwrap_or_default()
+    }
+
+    fn save(&self) {
+        storage::save(
+            DAILY_KEY,
+            &format!(
+                "{} {} {} {}\n",
+                self.date, self.score, self.stage, self.finished
+            ),
+        );
+    }
+
+    pub fn attempted_today(&self) -> bool {
+        self.date == today()
+    }
+
+    pub fn start(&mut self) {
+        *self = DailyChallenge {
+            date: today(),
+            score: 0,
+            stage: 1,
+            finished: false,
+        };
+        self.save();
+    }
+
+    pub fn finish(&mut self, score: u64, stage: usize) {
+        self.score = score;
+        self.stage = stage;
+        self.finished = true;
+        self.save();
+    }
+
+    /// How today's attempt went, or `None` if it hasn't been made.
+    pub fn result(&self) -> Option<String> {
+        if !self.attempted_today() {
+            None
+        } else if self.finished {
+            Some(format!(
+                "Today's challenge: {} points, stage {}",
+                self.score, self.stage
+            ))
+        } else {
+            Some("Today's attempt hasn't been finished".to_string())
+        }
+    }
+}
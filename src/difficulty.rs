@@ -0,0 +1,96 @@
+use super::*;
+
+/// Hornet speed and count multipliers allowed in a custom difficulty.
+pub const FACTOR_RANGE: (f32, f32) = (0.5, 2.0);
+pub const HP_RANGE: (i32, i32) = (1, 10);
+pub const DAMAGE_RANGE: (i32, i32) = (1, 3);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Difficulty {
+    Easy,
+    #[default]
+    Normal,
+    Hard,
+    Nightmare,
+    /// Whatever tuning the player has set up in the settings.
+    Custom,
+}
+
+impl Difficulty {
+    pub const ALL: [Difficulty; 5] = [
+        Difficulty::Easy,
+        Difficulty::Normal,
+        Difficulty::Hard,
+        Difficulty::Nightmare,
+        Difficulty::Custom,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Difficulty::Easy => "Easy",
+            Difficulty::Normal => "Normal",
+            Difficulty::Hard => "Hard",
+            Difficulty::Nightmare => "Nightmare",
+            Difficulty::Custom => "Custom",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Difficulty::ALL
+            .iter()
+            .copied()
+            .find(|difficulty| difficulty.name() == name)
+    }
+
+    /// The preset's tuning, or `None` for `Custom`.
+    pub fn preset(self) -> Option<Tuning> {
+        let (speed, count, player_hp, hive_hp, damage) = match self {
+            Difficulty::Easy => (0.7, 0.6, 5, 8, 1),
+            Difficulty::Normal => (1.0, 1.0, 3, 5, 1),
+            Difficulty::Hard => (1.25, 1.3, 2, 4, 1),
+            Difficulty::Nightmare => (1.5, 1.6, 2, 4, 2),
+            Difficulty::Custom => return None,
+        };
+
+        Some(Tuning {
+            speed,
+            count,
+            player_hp,
+            hive_hp,
+            damage,
+        })
+    }
+}
+
+/// The numbers a difficulty comes down to.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Tuning {
+    /// Scales how fast hornets fly.
+    pub speed: f32,
+    /// Scales how many hornets a stage sends, and how many fly at once.
+    pub count: f32,
+    pub player_hp: i32,
+    pub hive_hp: i32,
+    /// What one sting takes off the bee or a hive.
+    pub damage: i32,
+}
+
+impl Default for Tuning {
+    fn default() -> Self {
+        Difficulty::Normal.preset().unwrap()
+    }
+}
+
+impl Tuning {
+    /// Pulls anything out of range, as from a hand-edited settings file, back
+    /// into it.
+    pub fn clamped(self) -> Self {
+        Tuning {
+            speed: self.speed.clamp(FACTOR_RANGE.0, FACTOR_RANGE.1),
+            count: self.count.clamp(FACTOR_RANGE.0, FACTOR_RANGE.1),
+            player_hp: self.player_hp.clamp(HP_RANGE.0, HP_RANGE.1),
+            hive_hp: self.hive_hp.clamp(HP_RANGE.0, HP_RANGE.1),
+            damage: self.damage.clamp(DAMAGE_RANGE.0, DAMAGE_RANGE.1),
+        }
+    }
+}
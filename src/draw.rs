@@ -1,27 +1,431 @@
 use macroquad::prelude::*;
 
+use crate::menus::MenuId;
+use crate::view::{view_scale, virtual_mouse_position};
+
+/// Draws text sized in virtual units, rasterized at the window's real
+/// resolution so it stays crisp however the view is scaled.
+pub fn draw_scaled_text(text: &str, x: f32, y: f32, font_size: u16, color: Color) {
+    let scale = view_scale();
+    draw_text_ex(
+        text,
+        x,
+        y,
+        TextParams {
+            font_size: ((font_size as f32 * scale) as u16).max(1),
+            font_scale: 1.0 / scale,
+            color,
+            ..Default::default()
+        },
+    );
+}
+
+pub fn measure_scaled_text(text: &str, font_size: u16) -> TextDimensions {
+    let scale = view_scale();
+    measure_text(
+        text,
+        None,
+        ((font_size as f32 * scale) as u16).max(1),
+        1.0 / scale,
+    )
+}
+
 pub fn draw_centered_text(text: &str, x: f32, y: f32, font_size: u16, color: Color) {
-    let size = measure_text(text, None, font_size, 1.0);
-    draw_text(
+    let size = measure_scaled_text(text, font_size);
+    draw_scaled_text(
         text,
         x - size.width / 2.0,
         y - size.height / 2.0,
-        font_size as f32,
+        font_size,
         color,
     );
 }
 
-pub fn draw_v_centered_text(text: &str, x: f32, y: f32, font_size: u16, color: Color) {
-    let size = measure_text(text, None, font_size, 1.0);
-    draw_text(text, x, y - size.height / 2.0, font_size as f32, color);
-}
-
 pub fn draw_h_centered_text(text: &str, x: f32, y: f32, font_size: u16, color: Color) {
-    let size = measure_text(text, None, font_size, 1.0);
-    draw_text(text, x - size.width / 2.0, y, font_size as f32, color);
+    let size = measure_scaled_text(text, font_size);
+    draw_scaled_text(text, x - size.width / 2.0, y, font_size, color);
 }
 
 pub fn draw_right_aligned_text(text: &str, x: f32, y: f32, font_size: u16, color: Color) {
-    let size = measure_text(text, None, font_size, 1.0);
-    draw_text(text, x - size.width, y, font_size as f32, color);
+    let size = measure_scaled_text(text, font_size);
+    draw_scaled_text(text, x - size.width, y, font_size, color);
+}
+
+/// `m:ss.ss`, for clocks and split times.
+pub fn format_time(secs: f32) -> String {
+    let secs = secs.max(0.0);
+    format!("{}:{:05.2}", (secs / 60.0) as u32, secs % 60.0)
+}
+
+const MENU_ROW_HEIGHT: f32 = 70.0;
+const MENU_ROW_WIDTH: f32 = 900.0;
+const MENU_FONT_SIZE: u16 = 48;
+const SLIDER_WIDTH: f32 = 300.0;
+/// Between a row's label and its value.
+const MENU_GUTTER: f32 = 20.0;
+
+/// What a menu row does when activated or adjusted.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Widget {
+    Button,
+    Toggle(bool),
+    /// From `min` to `max`, adjusted in `step`s. Shown as a percentage, or as
+    /// a whole number when stepping by whole numbers.
+    Slider {
+        value: f32,
+        min: f32,
+        max: f32,
+        step: f32,
+    },
+    Choice {
+        options: Vec<String>,
+        selected: usize,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct MenuItem {
+    /// What the row is, for acting on it and reading it back.
+    pub id: MenuId,
+    pub label: String,
+    pub widget: Widget,
+    /// Disabled rows are greyed out and skipped over.
+    pub enabled: bool,
+}
+
+impl MenuItem {
+    fn new(id: MenuId, label: &str, widget: Widget) -> Self {
+        MenuItem {
+            id,
+            label: label.to_string(),
+            widget,
+            enabled: true,
+        }
+    }
+
+    pub fn button(id: MenuId, label: &str) -> Self {
+        MenuItem::new(id, label, Widget::Button)
+    }
+
+    pub fn toggle(id: MenuId, label: &str, on: bool) -> Self {
+        MenuItem::new(id, label, Widget::Toggle(on))
+    }
+
+    pub fn slider(id: MenuId, label: &str, value: f32, step: f32) -> Self {
+        MenuItem::range_slider(id, label, value, 0.0, 1.0, step)
+    }
+
+    pub fn range_slider(
+        id: MenuId,
+        label: &str,
+        value: f32,
+        min: f32,
+        max: f32,
+        step: f32,
+    ) -> Self {
+        MenuItem::new(
+            id,
+            label,
+            Widget::Slider {
+                value,
+                min,
+                max,
+                step,
+            },
+        )
+    }
+
+    pub fn choice(id: MenuId, label: &str, options: &[&str], selected: usize) -> Self {
+        MenuItem::new(
+            id,
+            label,
+            Widget::Choice {
+                options: options.iter().map(|o| o.to_string()).collect(),
+                selected,
+            },
+        )
+    }
+
+    pub fn enabled(mut self, enabled: bool) -> Self {
+        self.enabled = enabled;
+        self
+    }
+
+    pub fn is_on(&self) -> bool {
+        self.widget == Widget::Toggle(true)
+    }
+
+    pub fn value(&self) -> f32 {
+        match self.widget {
+            Widget::Slider { value, .. } => value,
+            _ => 0.0,
+        }
+    }
+
+    pub fn selected(&self) -> usize {
+        match self.widget {
+            Widget::Choice { selected, .. } => selected,
+            _ => 0,
+        }
+    }
+
+    /// Nudges toggles, sliders and choices by one notch, returning whether
+    /// anything changed.
+    fn adjust(&mut self, delta: i32) -> bool {
+        match &mut self.widget {
+            Widget::Button => false,
+            Widget::Toggle(on) => {
+                *on = !*on;
+                true
+            }
+            Widget::Slider {
+                value,
+                min,
+                max,
+                step,
+            } => {
+                let old = *value;
+                *value = (*value + *step * delta as f32).clamp(*min, *max);
+                *value != old
+            }
+            Widget::Choice { options, selected } => {
+                let len = options.len() as i32;
+                *selected = (*selected as i32 + delta).rem_euclid(len) as usize;
+                len > 1
+            }
+        }
+    }
+
+    fn value_text(&self) -> Option<String> {
+        match &self.widget {
+            Widget::Button => None,
+            Widget::Toggle(on) => Some(if *on { "On" } else { "Off" }.to_string()),
+            Widget::Slider { value, step, .. } if *step >= 1.0 => Some(format!("{:.0}", value)),
+            Widget::Slider { value, .. } => Some(format!("{:.0}%", value * 100.0)),
+            Widget::Choice { options, selected } => Some(format!("< {} >", options[*selected])),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MenuEvent {
+    /// A button was pressed.
+    Activated(MenuId),
+    /// A toggle, slider or choice changed value.
+    Changed(MenuId),
+    Back,
+}
+
+/// A vertical list of widgets in HUD space, driven by the arrow keys or WASD,
+/// Enter or Space, Escape, and the mouse. Macroquad has no gamepad input yet,
+/// so pads only work through whatever maps them to keys.
+#[derive(Debug)]
+pub struct Menu {
+    pub items: Vec<MenuItem>,
+    pub selected: usize,
+    /// Center of the first row.
+    pos: Vec2,
+    /// Where the mouse was last frame, so a resting cursor doesn't fight the
+    /// keyboard over the selection.
+    last_mouse: Vec2,
+}
+
+impl Menu {
+    pub fn new(pos: Vec2, items: Vec<MenuItem>) -> Self {
+        let selected = items.iter().position(|item| item.enabled).unwrap_or(0);
+
+        Menu {
+            items,
+            selected,
+            pos,
+            last_mouse: virtual_mouse_position(),
+        }
+    }
+
+    /// The row with `id`. Every page builds the rows it reads back, so a
+    /// missing one is a bug.
+    pub fn item(&self, id: MenuId) -> &MenuItem {
+        self.items
+            .iter()
+            .find(|item| item.id == id)
+            .unwrap_or_else(|| panic!("menu has no {:?} row", id))
+    }
+
+    pub fn item_mut(&mut self, id: MenuId) -> &mut MenuItem {
+        self.items
+            .iter_mut()
+            .find(|item| item.id == id)
+            .unwrap_or_else(|| panic!("menu has no {:?} row", id))
+    }
+
+    fn row_rect(&self, i: usize) -> Rect {
+        Rect::new(
+            self.pos.x - MENU_ROW_WIDTH / 2.0,
+            self.pos.y + MENU_ROW_HEIGHT * (i as f32 - 0.5),
+            MENU_ROW_WIDTH,
+            MENU_ROW_HEIGHT,
+        )
+    }
+
+    fn slider_rect(&self, i: usize) -> Rect {
+        let row = self.row_rect(i);
+        Rect::new(
+            self.pos.x + MENU_GUTTER,
+            row.y + row.h * 0.4,
+            SLIDER_WIDTH,
+            row.h * 0.2,
+        )
+    }
+
+    /// Moves the selection to the next enabled row in `direction`, wrapping.
+    fn step_selection(&mut self, direction: i32) {
+        let len = self.items.len() as i32;
+        let mut i = self.selected as i32;
+        for _ in 0..len {
+            i = (i + direction).rem_euclid(len);
+            if self.items[i as usize].enabled {
+                self.selected = i as usize;
+                return;
+            }
+        }
+    }
+
+    pub fn update(&mut self) -> Option<MenuEvent> {
+        if self.items.is_empty() {
+            return None;
+        }
+
+        if is_key_pressed(KeyCode::Escape) || is_key_pressed(KeyCode::Backspace) {
+            return Some(MenuEvent::Back);
+        }
+        if is_key_pressed(KeyCode::Up) || is_key_pressed(KeyCode::W) {
+            self.step_selection(-1);
+        }
+        if is_key_pressed(KeyCode::Down) || is_key_pressed(KeyCode::S) {
+            self.step_selection(1);
+        }
+
+        let mouse = virtual_mouse_position();
+        let mouse_moved = mouse != self.last_mouse;
+        let hovered = (0..self.items.len())
+            .find(|&i| self.items[i].enabled && self.row_rect(i).contains(mouse));
+        if let Some(i) = hovered {
+            if mouse_moved {
+                self.selected = i;
+            }
+        }
+        self.last_mouse = mouse;
+
+        let selected = self.selected;
+        let bar = self.slider_rect(selected);
+        let item = &mut self.items[selected];
+        if !item.enabled {
+            return None;
+        }
+
+        let left = is_key_pressed(KeyCode::Left) || is_key_pressed(KeyCode::A);
+        let right = is_key_pressed(KeyCode::Right) || is_key_pressed(KeyCode::D);
+        let activate = is_key_pressed(KeyCode::Enter) || is_key_pressed(KeyCode::Space);
+        let clicked = hovered == Some(selected) && is_mouse_button_pressed(MouseButton::Left);
+        // a held button only drags while the mouse moves, so the keyboard
+        // still works with it down
+        let on_bar = hovered == Some(selected) && mouse.x >= bar.left() - MENU_GUTTER;
+        let dragged =
+            on_bar && (clicked || (mouse_moved && is_mouse_button_down(MouseButton::Left)));
+
+        let changed = match &mut item.widget {
+            Widget::Button => {
+                return if activate || clicked {
+                    Some(MenuEvent::Activated(item.id))
+                } else {
+                    None
+                };
+            }
+            Widget::Slider {
+                value,
+                min,
+                max,
+                step,
+            } if dragged => {
+                let old = *value;
+                let fill = ((mouse.x - bar.x) / bar.w).clamp(0.0, 1.0);
+                *value = *min + fill * (*max - *min);
+                // whole-number sliders snap to their steps
+                if *step >= 1.0 {
+                    *value = *min + ((*value - *min) / *step).round() * *step;
+                }
+                *value != old
+            }
+            // clicking a slider's label does nothing
+            Widget::Slider { .. } if clicked && !(left || right || activate) => false,
+            _ if left => item.adjust(-1),
+            _ if right || activate || clicked => item.adjust(1),
+            _ => false,
+        };
+
+        if changed {
+            Some(MenuEvent::Changed(self.items[selected].id))
+        } else {
+            None
+        }
+    }
+
+    pub fn draw(&self) {
+        for (i, item) in self.items.iter().enumerate() {
+            let row = self.row_rect(i);
+            let y = row.center().y + measure_scaled_text("A", MENU_FONT_SIZE).height / 2.0;
+            let color = if !item.enabled {
+                DARKGRAY
+            } else if i == self.selected {
+                GOLD
+            } else {
+                WHITE
+            };
+
+            if i == self.selected && item.enabled {
+                draw_rectangle(row.x, row.y, row.w, row.h, Color::new(1.0, 1.0, 1.0, 0.1));
+            }
+
+            match item.value_text() {
+                None => draw_h_centered_text(&item.label, self.pos.x, y, MENU_FONT_SIZE, color),
+                Some(value) => {
+                    draw_right_aligned_text(
+                        &item.label,
+                        self.pos.x - MENU_GUTTER,
+                        y,
+                        MENU_FONT_SIZE,
+                        color,
+                    );
+
+                    if let Widget::Slider {
+                        value: fill,
+                        min,
+                        max,
+                        ..
+                    } = item.widget
+                    {
+                        let fill = (fill - min) / (max - min);
+                        let bar = self.slider_rect(i);
+                        draw_rectangle(bar.x, bar.y, bar.w, bar.h, Color::new(1.0, 1.0, 1.0, 0.2));
+                        draw_rectangle(bar.x, bar.y, bar.w * fill, bar.h, color);
+                        draw_scaled_text(
+                            &value,
+                            bar.right() + MENU_GUTTER,
+                            y,
+                            MENU_FONT_SIZE,
+                            color,
+                        );
+                    } else {
+                        draw_scaled_text(
+                            &value,
+                            self.pos.x + MENU_GUTTER,
+                            y,
+                            MENU_FONT_SIZE,
+                            color,
+                        );
+                    }
+                }
+            }
+        }
+    }
 }
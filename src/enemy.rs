@@ -1,11 +1,87 @@
 use super::*;
 
-use macroquad::rand::ChooseRandom;
-
 const IMMOBILE_VOID_TIME_SECS: f64 = 5.0;
+/// Hornets start winding up their sting this close to their target.
+const WINDUP_DISTANCE: f32 = 150.0;
+const WINDUP_JITTER: [f32; 4] = [0.0, 3.0, 0.0, -3.0];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EnemyKind {
+    /// Small, fast and fragile.
+    Scout,
+    Worker,
+    /// Big, slow and tough.
+    Brute,
+}
+
+impl EnemyKind {
+    pub const ALL: [EnemyKind; 3] = [EnemyKind::Scout, EnemyKind::Worker, EnemyKind::Brute];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            EnemyKind::Scout => "Scout",
+            EnemyKind::Worker => "Worker",
+            EnemyKind::Brute => "Brute",
+        }
+    }
+
+    /// Tougher kinds start showing up in later stages.
+    pub fn random(stage: usize, rng: &mut Rng) -> Self {
+        let roll = rng.below(100);
+        if stage >= 2 && roll < 15 {
+            EnemyKind::Brute
+        } else if stage >= 1 && roll < 40 {
+            EnemyKind::Scout
+        } else {
+            EnemyKind::Worker
+        }
+    }
+
+    pub fn max_hp(self) -> i32 {
+        match self {
+            EnemyKind::Scout => 25,
+            EnemyKind::Worker => 50,
+            EnemyKind::Brute => 125,
+        }
+    }
+
+    pub fn speed_factor(self) -> f32 {
+        match self {
+            EnemyKind::Scout => 1.5,
+            EnemyKind::Worker => 1.0,
+            EnemyKind::Brute => 0.6,
+        }
+    }
+
+    /// Width and length, facing up.
+    pub fn size(self) -> (f32, f32) {
+        match self {
+            EnemyKind::Scout => (20.0, 70.0),
+            EnemyKind::Worker => (25.0, 100.0),
+            EnemyKind::Brute => (40.0, 140.0),
+        }
+    }
+
+    pub fn points(self) -> u64 {
+        match self {
+            EnemyKind::Scout => 150,
+            EnemyKind::Worker => 100,
+            EnemyKind::Brute => 300,
+        }
+    }
+
+    pub fn color(self) -> Color {
+        match self {
+            EnemyKind::Scout => Color::new(1.0, 0.75, 0.2, 1.0),
+            EnemyKind::Worker => ORANGE,
+            EnemyKind::Brute => Color::new(0.7, 0.3, 0.0, 1.0),
+        }
+    }
+}
 
 #[derive(Debug)]
 pub struct Enemy {
+    pub kind: EnemyKind,
     pub hp: i32,
     pub x: f32,
     pub y: f32,
@@ -14,70 +90,92 @@ pub struct Enemy {
     pub speed: f32,
     pub direction: Direction,
     pub target: Option<Vec2>,
+    /// Seconds left to flash white after being hit.
+    pub flash: f32,
+    pub wings: Animation,
+    /// Idle, winding up an attack, or dying.
+    pub body: Animation,
     last_position: (f32, f32, f64),
+    /// Picks targets, seeded from the spawn sequence so a seed plays the
+    /// same whatever else draws on the global `rand`.
+    rng: Rng,
 }
 
 impl Enemy {
-    pub fn with_speed(speed: f32) -> Self {
-        let direction = vec![
-            Direction::Up,
-            Direction::Right,
-            Direction::Down,
-            Direction::Left,
-        ]
-        .choose()
-        .copied()
-        .unwrap();
+    /// Spawns just outside a random edge of `bounds`, `speed` being scaled by
+    /// how fast this kind is.
+    pub fn new(kind: EnemyKind, speed: f32, bounds: Rect, rng: &mut Rng) -> Self {
+        let direction = *rng
+            .choose(&[
+                Direction::Up,
+                Direction::Right,
+                Direction::Down,
+                Direction::Left,
+            ])
+            .unwrap();
 
+        let (thickness, length) = kind.size();
         let (width, height) = match direction {
-            Direction::Up | Direction::Down => (25.0, 100.0),
-            Direction::Left | Direction::Right => (100.0, 25.0),
+            Direction::Up | Direction::Down => (thickness, length),
+            Direction::Left | Direction::Right => (length, thickness),
         };
 
         let x = match direction {
-            Direction::Up | Direction::Down => rand::gen_range(0.0, screen_width()),
-            Direction::Left => 0.0 - width,
-            Direction::Right => screen_width(),
+            Direction::Up | Direction::Down => rng.gen_range(bounds.left(), bounds.right()),
+            Direction::Left => bounds.left() - width,
+            Direction::Right => bounds.right(),
         };
 
         let y = match direction {
-            Direction::Left | Direction::Right => rand::gen_range(0.0, screen_width()),
-            Direction::Up => 0.0 - width,
-            Direction::Down => screen_height(),
+            Direction::Left | Direction::Right => rng.gen_range(bounds.top(), bounds.bottom()),
+            Direction::Up => bounds.top() - height,
+            Direction::Down => bounds.bottom(),
         };
 
         Enemy {
-            hp: 50,
+            kind,
+            hp: kind.max_hp(),
             x,
             y,
             width,
             height,
-            speed,
+            speed: speed * kind.speed_factor(),
             direction,
             target: None,
+            flash: 0.0,
+            wings: Animation::new(WING_FLAP),
+            body: Default::default(),
             last_position: (x, y, get_time()),
+            rng: Rng::new(rng.next_u32() as u64),
         }
     }
 
-    pub fn desired_movement(&mut self, hives: &Vec<Hive>) -> Vec2 {
-        if self.target.is_none() || rand::gen_range(0, 100) == 0 {
-            self.target = hives.choose().map(|t| t.pos());
+    pub fn desired_movement(&mut self, hives: &[Hive]) -> Vec2 {
+        // about once every 100 frames at the tuned rate
+        if self.target.is_none() || self.rng.gen_range(0.0, 1.0) < 0.01 * frame_steps() {
+            self.target = self.rng.choose(hives).map(|t| t.pos());
         }
 
         let target = self.target.unwrap();
 
-        Vec2::new(target.x - self.x, target.y - self.y).clamp_length(0.0, self.speed)
-    }
-}
+        if self.pos().distance(target) < WINDUP_DISTANCE {
+            self.body.play(ATTACK_WINDUP);
+        } else {
+            self.body.play(IDLE);
+        }
 
-impl Default for Enemy {
-    fn default() -> Enemy {
-        Enemy::with_speed(2.0)
+        Vec2::new(target.x - self.x, target.y - self.y)
+            .clamp_length(0.0, self.speed * frame_steps())
     }
 }
 
 impl Actor for Enemy {
     fn tick(&mut self) {
+        let dt = get_frame_time();
+        self.flash = (self.flash - dt).max(0.0);
+        self.wings.tick(dt);
+        self.body.tick(dt);
+
         let (x, y, time) = self.last_position;
         if x == self.x && y == self.y && (get_time() - time) > IMMOBILE_VOID_TIME_SECS {
             self.hp = 0;
@@ -116,20 +214,63 @@ impl Mobile for Enemy {
 
 impl Drawable for Enemy {
     fn draw(&self) {
-        let color = match self.hp {
-            0..=25 => RED,
-            _ => ORANGE,
+        if self.body.clip() == DEATH {
+            self.draw_dying();
+            return;
+        }
+
+        let wounded = self.hp <= self.kind.max_hp() / 2;
+        let color = if self.flash > 0.0 {
+            WHITE
+        } else if wounded {
+            RED
+        } else {
+            self.kind.color()
+        };
+
+        let tint = if wounded { PINK } else { WHITE };
+        let animation = if self.body.clip() == IDLE {
+            &self.wings
+        } else {
+            &self.body
+        };
+        let center = self.bounding_box().center();
+        let rotation = self.direction.angle() + PI;
+        if draw_animated_sprite(
+            HORNET,
+            animation,
+            center,
+            self.sprite_size(),
+            rotation,
+            tint,
+        ) {
+            if self.flash > 0.0 {
+                flash_animated_sprite(HORNET, animation, center, self.sprite_size(), rotation);
+            }
+            return;
+        }
+
+        // shake side to side while winding up
+        let jitter = if self.body.clip() == ATTACK_WINDUP {
+            WINDUP_JITTER[self.body.frame()]
+        } else {
+            0.0
+        };
+        let (x, y) = match self.direction {
+            Direction::Up | Direction::Down => (self.x + jitter, self.y),
+            Direction::Left | Direction::Right => (self.x, self.y + jitter),
         };
 
         let r = self.width.min(self.height) / 2.0;
+        let wing = r * wing_scale(&self.wings);
 
         // thorax
         match self.direction {
             Direction::Up | Direction::Down => {
-                draw_rectangle(self.x, self.y + r, self.width, self.height - 2.0 * r, color);
+                draw_rectangle(x, y + r, self.width, self.height - 2.0 * r, color);
             }
             Direction::Left | Direction::Right => {
-                draw_rectangle(self.x + r, self.y, self.width - 2.0 * r, self.height, color);
+                draw_rectangle(x + r, y, self.width - 2.0 * r, self.height, color);
             }
         }
 
@@ -137,15 +278,15 @@ impl Drawable for Enemy {
         match self.direction {
             Direction::Up | Direction::Down => {
                 draw_rectangle(
-                    self.x,
-                    self.y + self.height / 3.0 - self.height / 10.0,
+                    x,
+                    y + self.height / 3.0 - self.height / 10.0,
                     self.width,
                     self.height / 5.0,
                     BLACK,
                 );
                 draw_rectangle(
-                    self.x,
-                    self.y + self.height * 2.0 / 3.0 - self.height / 10.0,
+                    x,
+                    y + self.height * 2.0 / 3.0 - self.height / 10.0,
                     self.width,
                     self.height / 5.0,
                     BLACK,
@@ -153,15 +294,15 @@ impl Drawable for Enemy {
             }
             Direction::Left | Direction::Right => {
                 draw_rectangle(
-                    self.x + self.width / 3.0 - self.width / 10.0,
-                    self.y,
+                    x + self.width / 3.0 - self.width / 10.0,
+                    y,
                     self.width / 5.0,
                     self.height,
                     BLACK,
                 );
                 draw_rectangle(
-                    self.x + self.width * 2.0 / 3.0 - self.width / 10.0,
-                    self.y,
+                    x + self.width * 2.0 / 3.0 - self.width / 10.0,
+                    y,
                     self.width / 5.0,
                     self.height,
                     BLACK,
@@ -172,27 +313,80 @@ impl Drawable for Enemy {
         // ends
         match self.direction {
             Direction::Up | Direction::Down => {
-                draw_circle(self.x + r, self.y + r, r, color);
-                draw_circle(self.x + r, self.y + self.height - r, r, color);
+                draw_circle(x + r, y + r, r, color);
+                draw_circle(x + r, y + self.height - r, r, color);
             }
             Direction::Left | Direction::Right => {
-                draw_circle(self.x + r, self.y + r, r, color);
-                draw_circle(self.x + self.width - r, self.y + r, r, color);
+                draw_circle(x + r, y + r, r, color);
+                draw_circle(x + self.width - r, y + r, r, color);
             }
         }
 
         // wings
         match self.direction {
             Direction::Up | Direction::Down => {
-                let y = self.y + self.height / 2.0;
-                draw_circle(self.x - r, y, r, LIGHTGRAY);
-                draw_circle(self.x + self.width + r, y, r, LIGHTGRAY);
+                let y = y + self.height / 2.0;
+                draw_circle(x - r, y, wing, LIGHTGRAY);
+                draw_circle(x + self.width + r, y, wing, LIGHTGRAY);
+            }
+            Direction::Left | Direction::Right => {
+                let x = x + self.width / 2.0;
+                draw_circle(x, y - r, wing, LIGHTGRAY);
+                draw_circle(x, y + self.height + r, wing, LIGHTGRAY);
+            }
+        }
+    }
+}
+
+impl Enemy {
+    /// Starts the death animation, after which the hornet can be removed.
+    pub fn die(&mut self) {
+        self.body.restart(DEATH);
+    }
+
+    /// Size when facing up; hornets fly in away from the edge they spawned on.
+    fn sprite_size(&self) -> Vec2 {
+        match self.direction {
+            Direction::Up | Direction::Down => Vec2::new(self.width, self.height),
+            Direction::Left | Direction::Right => Vec2::new(self.height, self.width),
+        }
+    }
+
+    fn draw_dying(&self) {
+        let t = self.body.progress();
+        let center = self.bounding_box().center();
+        let fade = 1.0 - t;
+
+        if draw_animated_sprite(
+            HORNET,
+            &self.body,
+            center,
+            self.sprite_size() * fade,
+            self.direction.angle() + PI + t * PI,
+            Color::new(1.0, 1.0, 1.0, fade),
+        ) {
+            return;
+        }
+
+        // curl up and fade away, wings drifting off
+        let r = self.width.min(self.height) / 2.0;
+        let drift = r * 3.0 * t;
+        let wing_color = Color::new(0.78, 0.78, 0.78, fade);
+        match self.direction {
+            Direction::Up | Direction::Down => {
+                draw_circle(center.x - r - drift, center.y, r * fade, wing_color);
+                draw_circle(center.x + r + drift, center.y, r * fade, wing_color);
             }
             Direction::Left | Direction::Right => {
-                let x = self.x + self.width / 2.0;
-                draw_circle(x, self.y - r, r, LIGHTGRAY);
-                draw_circle(x, self.y + self.height + r, r, LIGHTGRAY);
+                draw_circle(center.x, center.y - r - drift, r * fade, wing_color);
+                draw_circle(center.x, center.y + r + drift, r * fade, wing_color);
             }
         }
+        draw_circle(
+            center.x,
+            center.y,
+            r * 1.5 * fade,
+            Color::new(0.9, 0.3, 0.1, fade),
+        );
     }
 }
@@ -0,0 +1,103 @@
+use super::*;
+
+/// Things that happened during a tick. The simulation only records them; audio,
+/// particles, screen shake and anything else reacting to gameplay consume them
+/// once the tick is over.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GameEvent {
+    StageStarted {
+        stage: usize,
+    },
+    Shot {
+        pos: Vec2,
+        direction: Direction,
+    },
+    /// A stinger was stopped by something other than a hornet.
+    ShotBlocked {
+        pos: Vec2,
+        direction: Direction,
+    },
+    EnemyHit {
+        pos: Vec2,
+        direction: Direction,
+        damage: i32,
+    },
+    EnemyKilled {
+        pos: Vec2,
+        kind: EnemyKind,
+    },
+    /// A hornet stung a hive, dying in the process.
+    EnemyStung {
+        pos: Vec2,
+    },
+    HiveDamaged {
+        pos: Vec2,
+        hp: i32,
+        /// HP the sting took off.
+        lost: i32,
+    },
+    HiveDestroyed {
+        pos: Vec2,
+    },
+    PlayerDamaged {
+        hp: i32,
+        /// HP the sting took off.
+        lost: i32,
+    },
+    StageCleared {
+        stage: usize,
+        /// Hives still at full HP.
+        full_hives: usize,
+    },
+    GameOver {
+        stage: usize,
+    },
+    /// A bee ran out of HP with another still flying.
+    PlayerDowned {
+        pos: Vec2,
+    },
+    PlayerRevived {
+        pos: Vec2,
+    },
+    /// A time or score attack run reached its end.
+    RunFinished {
+        stage: usize,
+    },
+}
+
+impl GameEvent {
+    pub fn sound(&self) -> Option<SoundEffect> {
+        match self {
+            GameEvent::Shot { .. } => Some(SoundEffect::Shoot),
+            GameEvent::EnemyHit { .. } => Some(SoundEffect::Hit),
+            GameEvent::EnemyKilled { .. } => Some(SoundEffect::Kill),
+            GameEvent::HiveDamaged { .. } => Some(SoundEffect::HiveDamage),
+            GameEvent::PlayerDamaged { .. } => Some(SoundEffect::PlayerDamage),
+            GameEvent::StageCleared { .. }
+            | GameEvent::RunFinished { .. }
+            | GameEvent::PlayerRevived { .. } => Some(SoundEffect::StageClear),
+            GameEvent::GameOver { .. } => Some(SoundEffect::GameOver),
+            GameEvent::StageStarted { .. }
+            | GameEvent::PlayerDowned { .. }
+            | GameEvent::ShotBlocked { .. }
+            | GameEvent::EnemyStung { .. }
+            | GameEvent::HiveDestroyed { .. } => None,
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct EventQueue {
+    events: Vec<GameEvent>,
+}
+
+impl EventQueue {
+    pub fn push(&mut self, event: GameEvent) {
+        self.events.push(event);
+    }
+
+    /// Hands over everything emitted since the last drain, oldest first.
+    pub fn drain(&mut self) -> impl Iterator<Item = GameEvent> + '_ {
+        self.events.drain(..)
+    }
+}
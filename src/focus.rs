@@ -0,0 +1,31 @@
+//! Noticing when the player has switched away from the game.
+
+use super::*;
+
+/// A frame taking longer than this means the game wasn't being shown. On
+/// native it's the only sign, so it's set well past any hitch a busy machine
+/// or a slow load could cause.
+#[cfg(not(target_arch = "wasm32"))]
+const MAX_FRAME_GAP_SECS: f32 = 5.0;
+/// Browsers stop drawing hidden tabs outright, so a short stall will do.
+#[cfg(target_arch = "wasm32")]
+const MAX_FRAME_GAP_SECS: f32 = 0.5;
+
+#[cfg(target_arch = "wasm32")]
+extern "C" {
+    /// From the plugin in `web/index.html`; clears itself once read.
+    fn hornet_focus_lost() -> i32;
+}
+
+/// Whether the window lost focus since the last call. Macroquad 0.3 passes on
+/// no focus or minimize events on native, so there this is only ever a long
+/// stall between frames, as when a minimized window stops being drawn;
+/// switching to another window that leaves the game drawing goes unnoticed.
+pub fn focus_lost() -> bool {
+    let stalled = get_frame_time() > MAX_FRAME_GAP_SECS;
+
+    #[cfg(target_arch = "wasm32")]
+    let stalled = unsafe { hornet_focus_lost() != 0 } || stalled;
+
+    stalled
+}
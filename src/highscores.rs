@@ -0,0 +1,268 @@
+use super::*;
+
+use std::cmp::Reverse;
+
+const HIGH_SCORES_KEY: &str = "highscores.txt";
+const ENDLESS_SCORES_KEY: &str = "endless_scores.txt";
+const SCORE_ATTACK_SCORES_KEY: &str = "score_attack.txt";
+const TIME_ATTACK_KEY: &str = "time_attack.txt";
+pub const MAX_ENTRIES: usize = 10;
+pub const INITIALS_LEN: usize = 3;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HighScore {
+    pub initials: String,
+    pub score: u64,
+    /// Counted from 1, as shown in the HUD.
+    pub stage: usize,
+    pub hives_saved: usize,
+    /// What the RNG was seeded with for the run.
+    pub seed: u64,
+    /// `YYYY-MM-DD`.
+    pub date: String,
+}
+
+impl HighScore {
+    /// Dated today, with the initials still to be filled in.
+    pub fn new(score: u64, stage: usize, hives_saved: usize, seed: u64) -> Self {
+        HighScore {
+            initials: String::new(),
+            score,
+            stage,
+            hives_saved,
+            seed,
+            date: date_string(date::now()),
+        }
+    }
+
+    fn parse(line: &str) -> Option<Self> {
+        let mut fields = line.split_whitespace();
+        Some(HighScore {
+            initials: fields.next()?.to_string(),
+            score: fields.next()?.parse().ok()?,
+            stage: fields.next()?.parse().ok()?,
+            hives_saved: fields.next()?.parse().ok()?,
+            seed: fields.next()?.parse().ok()?,
+            date: fields.next()?.to_string(),
+        })
+    }
+}
+
+/// The best runs, highest score first.
+#[derive(Debug)]
+pub struct HighScores {
+    key: &'static str,
+    /// What the stage column is headed, since endless runs count waves.
+    stage_title: &'static str,
+    entries: Vec<HighScore>,
+}
+
+impl HighScores {
+    /// The table for the modes with stages.
+    pub fn load() -> Self {
+        HighScores::load_from(HIGH_SCORES_KEY, "Stage")
+    }
+
+    /// Endless runs, kept apart from the rest. Their hives are the ones still
+    /// standing at the end.
+    pub fn load_endless() -> Self {
+        HighScores::load_from(ENDLESS_SCORES_KEY, "Wave")
+    }
+
+    /// Score attack runs, which all get the same three minutes.
+    pub fn load_score_attack() -> Self {
+        HighScores::load_from(SCORE_ATTACK_SCORES_KEY, "Stage")
+    }
+
+    /// One `initials score stage hives seed date` line per entry; unreadable
+    /// lines are skipped.
+    fn load_from(key: &'static str, stage_title: &'static str) -> Self {
+        let mut entries: Vec<_> = storage::load(key)
+            .unwrap_or_default()
+            .lines()
+            .filter_map(HighScore::parse)
+            .collect();
+        entries.sort_by_key(|e| Reverse(e.score));
+        entries.truncate(MAX_ENTRIES);
+
+        HighScores {
+            key,
+            stage_title,
+            entries,
+        }
+    }
+
+    pub fn save(&self) {
+        let text: String = self
+            .entries
+            .iter()
+            .map(|e| {
+                format!(
+                    "{} {} {} {} {} {}\n",
+                    e.initials, e.score, e.stage, e.hives_saved, e.seed, e.date
+                )
+            })
+            .collect();
+        storage::save(self.key, &text);
+    }
+
+    /// Where a run scoring `score` would land, counted from 0, or `None` if it
+    /// doesn't make the table. Ties go below the entries already there.
+    pub fn placement(&self, score: u64) -> Option<usize> {
+        let rank = self.entries.iter().filter(|e| e.score >= score).count();
+        if score > 0 && rank < MAX_ENTRIES {
+            Some(rank)
+        } else {
+            None
+        }
+    }
+
+    pub fn insert(&mut self, entry: HighScore) -> Option<usize> {
+        let rank = self.placement(entry.score)?;
+        self.entries.insert(rank, entry);
+        self.entries.truncate(MAX_ENTRIES);
+        Some(rank)
+    }
+
+    /// The table, starting at `y` in HUD space, with the `highlight`ed row in
+    /// gold.
+    pub fn draw(&self, y: f32, highlight: Option<usize>) {
+        let columns = [
+            ("#", 300.0),
+            ("Name", 420.0),
+            ("Score", 760.0),
+            (self.stage_title, 920.0),
+            ("Hives", 1080.0),
+            ("Date", 1320.0),
+        ];
+        for (title, x) in &columns {
+            draw_right_aligned_text(title, *x, y, 36, GRAY);
+        }
+
+        for (i, e) in self.entries.iter().enumerate() {
+            let color = if highlight == Some(i) { GOLD } else { WHITE };
+            let row = [
+                (i + 1).to_string(),
+                e.initials.clone(),
+                e.score.to_string(),
+                e.stage.to_string(),
+                e.hives_saved.to_string(),
+                e.date.clone(),
+            ];
+            let y = y + 45.0 * (i + 1) as f32;
+            for (text, (_, x)) in row.iter().zip(&columns) {
+                draw_right_aligned_text(text, *x, y, 36, color);
+            }
+        }
+    }
+}
+
+/// The fastest time attack run, as the time into the run each stage was
+/// cleared at.
+#[derive(Debug, Default)]
+pub struct BestSplits {
+    pub splits: Vec<f32>,
+    /// `YYYY-MM-DD`.
+    pub date: String,
+}
+
+impl BestSplits {
+    /// Saved as one `date split split...` line.
+    pub fn load() -> Self {
+        let text = storage::load(TIME_ATTACK_KEY).unwrap_or_default();
+        let mut fields = text.split_whitespace();
+        let parse = || -> Option<BestSplits> {
+            let date = fields.next()?.to_string();
+            let splits = fields
+                .map(|split| split.parse().ok())
+                .collect::<Option<Vec<f32>>>()?;
+            Some(BestSplits { splits, date })
+        };
+
+        parse().unwrap_or_default()
+    }
+
+    fn save(&self) {
+        let splits: Vec<_> = self.splits.iter().map(|s| s.to_string()).collect();
+        storage::save(
+            TIME_ATTACK_KEY,
+            &format!("{} {}\n", self.date, splits.join(" ")),
+        );
+    }
+
+    pub fn total(&self) -> Option<f32> {
+        self.splits.last().copied()
+    }
+
+    /// Keeps a finished run's `splits` if they beat the best, returning
+    /// whether they did.
+    pub fn record(&mut self, splits: &[f32]) -> bool {
+        let total = match splits.last() {
+            Some(&total) => total,
+            None => return false,
+        };
+        if matches!(self.total(), Some(best) if best <= total) {
+            return false;
+        }
+
+        self.splits = splits.to_vec();
+        self.date = date_string(date::now());
+        self.save();
+        true
+    }
+}
+
+/// A time attack run's splits, starting at `y` in HUD space, each compared
+/// with the same point in the `best` run if there's one to compare with.
+pub fn draw_splits(splits: &[f32], best: &[f32], y: f32) {
+    let columns = [
+        ("Stage", 420.0),
+        ("Time", 680.0),
+        ("Total", 940.0),
+        ("vs best", 1200.0),
+    ];
+    for (title, x) in &columns {
+        draw_right_aligned_text(title, *x, y, 36, GRAY);
+    }
+
+    let mut previous = 0.0;
+    for (i, &split) in splits.iter().enumerate() {
+        let y = y + 45.0 * (i + 1) as f32;
+        let row = [
+            (i + 1).to_string(),
+            format_time(split - previous),
+            format_time(split),
+        ];
+        for (text, (_, x)) in row.iter().zip(&columns) {
+            draw_right_aligned_text(text, *x, y, 36, WHITE);
+        }
+        if let Some(&best) = best.get(i) {
+            let delta = split - best;
+            let color = if delta <= 0.0 { GREEN } else { RED };
+            draw_right_aligned_text(&format!("{:+.2}", delta), columns[3].1, y, 36, color);
+        }
+        previous = split;
+    }
+}
+
+/// Formats seconds since the Unix epoch as a UTC `YYYY-MM-DD` date.
+pub fn date_string(secs: f64) -> String {
+    // Howard Hinnant's days-to-civil algorithm, with eras of 400 years
+    let days = (secs / 86400.0).floor() as i64 + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    // months counted from March, so the leap day comes last
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    };
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
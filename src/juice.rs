@@ -0,0 +1,138 @@
+use super::*;
+
+/// Furthest the camera is ever thrown by shake, in virtual units.
+const MAX_SHAKE_OFFSET: f32 = 30.0;
+/// Degrees.
+const MAX_SHAKE_ROTATION: f32 = 2.0;
+/// Trauma lost per second.
+const TRAUMA_DECAY: f32 = 1.5;
+const FLASH_SECS: f32 = 0.08;
+const BLINKS_PER_SEC: f64 = 10.0;
+
+/// Accessibility option for how much screen shake, freezing and flashing the
+/// game uses.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum EffectsLevel {
+    #[default]
+    Full,
+    Reduced,
+    Off,
+}
+
+impl EffectsLevel {
+    pub const ALL: [EffectsLevel; 3] =
+        [EffectsLevel::Full, EffectsLevel::Reduced, EffectsLevel::Off];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            EffectsLevel::Full => "Full",
+            EffectsLevel::Reduced => "Reduced",
+            EffectsLevel::Off => "Off",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        EffectsLevel::ALL
+            .iter()
+            .copied()
+            .find(|level| level.name() == name)
+    }
+
+    fn intensity(self) -> f32 {
+        match self {
+            EffectsLevel::Full => 1.0,
+            EffectsLevel::Reduced => 0.35,
+            EffectsLevel::Off => 0.0,
+        }
+    }
+}
+
+/// Screen shake, hit-stop and flashes, all scaled by `level`.
+#[derive(Debug, Default)]
+pub struct Juice {
+    pub level: EffectsLevel,
+    /// 0 to 1, shake grows with its square so small knocks stay subtle.
+    trauma: f32,
+    hitstop: f32,
+}
+
+impl Juice {
+    pub fn with_level(level: EffectsLevel) -> Self {
+        Juice {
+            level,
+            ..Default::default()
+        }
+    }
+
+    pub fn add_trauma(&mut self, amount: f32) {
+        self.trauma = (self.trauma + amount * self.level.intensity()).min(1.0);
+    }
+
+    /// Freezes the simulation for a moment to make a hit land.
+    pub fn hitstop(&mut self, secs: f32) {
+        if self.level == EffectsLevel::Full {
+            self.hitstop = self.hitstop.max(secs);
+        }
+    }
+
+    pub fn is_frozen(&self) -> bool {
+        self.hitstop > 0.0
+    }
+
+    /// How long something that was just hit should flash white.
+    pub fn flash_secs(&self) -> f32 {
+        if self.level == EffectsLevel::Off {
+            0.0
+        } else {
+            FLASH_SECS
+        }
+    }
+
+    /// Whether invulnerability is shown by blinking rather than a flat colour.
+    pub fn blinks(&self) -> bool {
+        self.level != EffectsLevel::Off
+    }
+
+    /// Whether a blinking sprite should be drawn this frame.
+    pub fn blink_visible(&self) -> bool {
+        (get_time() * BLINKS_PER_SEC) as u64 & 1 == 0
+    }
+
+    pub fn tick(&mut self, dt: f32) {
+        self.trauma = (self.trauma - TRAUMA_DECAY * dt).max(0.0);
+        self.hitstop = (self.hitstop - dt).max(0.0);
+    }
+
+    pub fn handle(&mut self, event: &GameEvent) {
+        match event {
+            GameEvent::EnemyKilled { .. } => {
+                self.add_trauma(0.15);
+                self.hitstop(0.04);
+            }
+            GameEvent::HiveDamaged { .. } => self.add_trauma(0.3),
+            GameEvent::HiveDestroyed { .. } => self.add_trauma(0.5),
+            GameEvent::PlayerDamaged { .. } => self.add_trauma(0.6),
+            _ => {}
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.trauma = 0.0;
+        self.hitstop = 0.0;
+    }
+
+    /// Applies the current shake to `camera`.
+    pub fn shake(&self, camera: &mut Camera2D) {
+        let shake = self.trauma * self.trauma;
+        if shake <= 0.0 {
+            return;
+        }
+
+        // cheap smooth noise, different frequencies per axis so it doesn't look periodic
+        let t = get_time() as f32;
+        let noise = |freq: f32, phase: f32| (t * freq + phase).sin() * (t * freq * 0.7).cos();
+
+        camera.target += Vec2::new(noise(37.0, 0.0), noise(41.0, 1.3)) * MAX_SHAKE_OFFSET * shake;
+        camera.rotation += noise(29.0, 2.7) * MAX_SHAKE_ROTATION * shake;
+    }
+}
@@ -1,40 +1,123 @@
-use macroquad::prelude::*;
+use macroquad::{
+    audio::{
+        load_sound, load_sound_from_bytes, play_sound, set_sound_volume, stop_sound,
+        PlaySoundParams, Sound,
+    },
+    file::FileError,
+    miniquad::{conf::Platform, date},
+    prelude::*,
+};
 
-use std::{convert::From, default::Default, ops::Neg};
+use std::{
+    default::Default,
+    f32::consts::{FRAC_PI_2, PI},
+    ops::Neg,
+};
 
+mod achievements;
+mod adaptive;
+mod animation;
+mod audio;
+mod camera;
+mod controls;
+mod daily;
+mod difficulty;
 mod draw;
 mod enemy;
+mod events;
+mod focus;
+mod highscores;
+mod juice;
+mod menus;
+mod particles;
 mod player;
+mod rng;
+mod scenes;
+mod score;
+mod settings;
+mod sprites;
+mod stats;
+mod storage;
+mod synth;
 mod terrain;
+mod view;
 mod world;
 
+use achievements::*;
+use adaptive::*;
+use animation::*;
+use audio::*;
+use camera::*;
+use controls::*;
+use daily::*;
+use difficulty::*;
 use draw::*;
 use enemy::*;
+use events::*;
+use focus::*;
+use highscores::*;
+use juice::*;
+use menus::*;
+use particles::*;
 use player::*;
+use rng::Rng;
+use scenes::*;
+use score::*;
+use settings::*;
+use sprites::*;
+use stats::*;
+use synth::SynthParams;
 use terrain::*;
+use view::*;
 use world::*;
 
 fn window_conf() -> Conf {
+    let settings = Settings::load();
+
     Conf {
         window_title: "Hornet Invaders".to_owned(),
-        window_width: 1600,
-        window_height: 1200,
+        window_width: settings.window_size.0,
+        window_height: settings.window_size.1,
+        fullscreen: settings.fullscreen,
+        window_resizable: true,
+        platform: Platform {
+            swap_interval: Some(if settings.vsync { 1 } else { 0 }),
+            ..Default::default()
+        },
         ..Default::default()
     }
 }
 
 #[macroquad::main(window_conf)]
 async fn main() {
-    let mut world = World::default();
+    Atlas::load().await;
+    let audio = Audio::load().await;
+
+    let mut world = World::new(audio);
+    let mut scenes = SceneStack::new(Box::new(MenuScene::new(MenuPage::Main, &world)));
 
     loop {
-        world.tick();
-        world.draw();
+        scenes.update(&mut world);
+        if scenes.is_empty() {
+            break;
+        }
+
+        scenes.draw(&world);
 
         next_frame().await
     }
 }
 
+/// Speeds are in pixels per frame at this rate.
+const TUNED_FPS: f32 = 60.0;
+
+/// How many frames at `TUNED_FPS` this one stands for, to scale movement by so
+/// it keeps pace with the clock with VSync off. Capped so a stall can't carry
+/// anything through a wall.
+fn frame_steps() -> f32 {
+    (get_frame_time() * TUNED_FPS).min(3.0)
+}
+
 trait Drawable {
     fn draw(&self);
 }
@@ -51,22 +134,20 @@ trait Positioned: Actor {
 trait Mobile: Positioned {
     fn move_by(&mut self, vector: Vec2);
 
-    fn screen_constrain(&mut self) {
+    fn constrain(&mut self, bounds: Rect) {
         let bb = self.bounding_box();
-        let screen_w = screen_width();
-        let x = if bb.x < 0.0 {
-            bb.x * -1.0
-        } else if bb.right() > screen_w {
-            screen_w - (bb.x + bb.w)
+        let x = if bb.x < bounds.left() {
+            bounds.left() - bb.x
+        } else if bb.right() > bounds.right() {
+            bounds.right() - (bb.x + bb.w)
         } else {
             0.0
         };
 
-        let screen_h = screen_height();
-        let y = if bb.y < 0.0 {
-            bb.y * -1.0
-        } else if bb.bottom() > screen_h {
-            screen_h - (bb.y + bb.h)
+        let y = if bb.y < bounds.top() {
+            bounds.top() - bb.y
+        } else if bb.bottom() > bounds.bottom() {
+            bounds.bottom() - (bb.y + bb.h)
         } else {
             0.0
         };
@@ -126,20 +207,12 @@ trait Actor: Drawable + std::fmt::Debug {
         self.bounding_box().overlaps(&other.bounding_box())
     }
 
-    fn fully_onscreen(&self) -> bool {
-        let bb = self.bounding_box();
-        bb.x > 0.0
-            && (bb.x + bb.w) < screen_width()
-            && bb.y > 0.0
-            && (bb.y + bb.h) < screen_height()
-    }
-
-    fn fully_offscreen(&self) -> bool {
+    fn fully_outside(&self, bounds: Rect) -> bool {
         let bb = self.bounding_box();
-        bb.x > screen_width()
-            || (bb.x + bb.w) < 0.0
-            || bb.y > screen_height()
-            || (bb.y + bb.h) < 0.0
+        bb.x > bounds.right()
+            || (bb.x + bb.w) < bounds.left()
+            || bb.y > bounds.bottom()
+            || (bb.y + bb.h) < bounds.top()
     }
 }
 
@@ -164,27 +237,41 @@ impl<A: Actor + Drawable> Actor for Box<A> {
         (**self).collides_with(other)
     }
     #[inline]
-    fn fully_onscreen(&self) -> bool {
-        (**self).fully_onscreen()
-    }
-    #[inline]
-    fn fully_offscreen(&self) -> bool {
-        (**self).fully_offscreen()
+    fn fully_outside(&self, bounds: Rect) -> bool {
+        (**self).fully_outside(bounds)
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-enum GameState {
-    Pregame,
-    Game,
-    Pause,
-    Victory,
-    Defeat,
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+enum GameMode {
+    #[default]
+    Classic,
+    /// Surviving hives, and their damage, carry over between stages.
+    Campaign,
+    /// One wave after another with no breaks, until the bee or the last hive
+    /// falls.
+    Endless,
+    /// Classic rules with a seed taken from the date, one attempt a day.
+    Daily,
+    /// Clear a fixed run of stages as fast as possible.
+    TimeAttack,
+    /// Score as much as possible before the clock runs out.
+    ScoreAttack,
 }
 
-impl Default for GameState {
-    fn default() -> Self {
-        GameState::Pregame
+impl GameMode {
+    /// Whether hives make honey that can be spent on new ones.
+    fn uses_honey(self) -> bool {
+        self == GameMode::Campaign || self == GameMode::Endless
+    }
+
+    /// Modes played to compare runs, which stick to Normal difficulty and
+    /// can't restart a stage.
+    fn is_competitive(self) -> bool {
+        matches!(
+            self,
+            GameMode::Daily | GameMode::TimeAttack | GameMode::ScoreAttack
+        )
     }
 }
 
@@ -205,4 +292,14 @@ impl Direction {
             Direction::Right => Vec2::new(1.0, 0.0),
         }
     }
+
+    /// Clockwise rotation from facing up, in radians.
+    pub fn angle(&self) -> f32 {
+        match self {
+            Direction::Up => 0.0,
+            Direction::Right => FRAC_PI_2,
+            Direction::Down => PI,
+            Direction::Left => -FRAC_PI_2,
+        }
+    }
 }
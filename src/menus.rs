@@ -0,0 +1,362 @@
+use super::*;
+
+/// The high-score page's boards, in the order its picker offers them, each
+/// with a mode that records to it.
+pub const BOARDS: [(&str, GameMode); 4] = [
+    ("Stages", GameMode::Classic),
+    ("Endless", GameMode::Endless),
+    ("Score Attack", GameMode::ScoreAttack),
+    ("Time Attack", GameMode::TimeAttack),
+];
+
+/// What a menu row is, so its action and setting go by that rather than by
+/// what it says or where it sits.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MenuId {
+    NewGame,
+    Continue,
+    Resume,
+    RestartStage,
+    QuitToMenu,
+    Quit,
+    Back,
+    /// Opens another page over this one.
+    Open(MenuPage),
+    /// Starts a run in the mode.
+    Start(GameMode),
+    Players,
+    Fullscreen,
+    WindowSize,
+    Vsync,
+    ShowFps,
+    Minimap,
+    MasterVolume,
+    MusicVolume,
+    SfxVolume,
+    /// Waits for a key to bind to the action.
+    Binding(Action),
+    ResetControls,
+    Difficulty,
+    HornetSpeed,
+    HornetCount,
+    BeeHp,
+    HiveHp,
+    StingDamage,
+    AdaptiveDifficulty,
+    ScreenEffects,
+    HoldToFire,
+    Board,
+}
+
+/// Screens of the main and pause menus, each opened as a `MenuScene`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MenuPage {
+    #[default]
+    Main,
+    Pause,
+    /// Read-only list of the current key bindings.
+    ControlsHelp,
+    Modes,
+    Settings,
+    Video,
+    Audio,
+    Controls,
+    PlayerTwoControls,
+    Gameplay,
+    Accessibility,
+    HighScores,
+    Achievements,
+}
+
+impl MenuPage {
+    pub fn title(self) -> &'static str {
+        match self {
+            MenuPage::Main | MenuPage::Modes => "Hornet Invaders",
+            MenuPage::Pause => "PAUSED",
+            MenuPage::ControlsHelp => "Controls",
+            MenuPage::Settings => "Settings",
+            MenuPage::Video => "Video",
+            MenuPage::Audio => "Audio",
+            MenuPage::Controls => "Controls",
+            MenuPage::PlayerTwoControls => "Player 2 Controls",
+            MenuPage::Gameplay => "Gameplay",
+            MenuPage::Accessibility => "Accessibility",
+            MenuPage::HighScores => "High Scores",
+            MenuPage::Achievements => "",
+        }
+    }
+
+    /// Which bee a controls page binds keys for, and the actions it lists,
+    /// in row order.
+    pub fn bindings(self) -> (usize, &'static [Action]) {
+        let player = match self {
+            MenuPage::PlayerTwoControls => 1,
+            _ => 0,
+        };
+        (player, Action::for_player(player))
+    }
+
+    pub fn menu(self, world: &World) -> Menu {
+        let settings = world.settings();
+        let center = VIRTUAL_WIDTH / 2.0;
+        let top = Vec2::new(center, 400.0);
+        let back = Vec2::new(center, VIRTUAL_HEIGHT - 80.0);
+
+        match self {
+            MenuPage::Main => {
+                let mut items = vec![
+                    MenuItem::button(MenuId::NewGame, "New Game"),
+                    MenuItem::button(MenuId::Continue, "Continue").enabled(world.run_in_progress()),
+                    MenuItem::button(MenuId::Open(MenuPage::Modes), "Modes"),
+                    MenuItem::button(MenuId::Open(MenuPage::Settings), "Settings"),
+                    MenuItem::button(MenuId::Open(MenuPage::HighScores), "High Scores"),
+                    MenuItem::button(MenuId::Open(MenuPage::Achievements), "Achievements"),
+                ];
+                // there's nothing to quit to in a browser tab
+                if !cfg!(target_arch = "wasm32") {
+                    items.push(MenuItem::button(MenuId::Quit, "Quit"));
+                }
+                Menu::new(Vec2::new(center, 480.0), items)
+            }
+            MenuPage::Pause => Menu::new(
+                Vec2::new(center, 480.0),
+                vec![
+                    MenuItem::button(MenuId::Resume, "Resume"),
+                    // competitive runs are one attempt, stages included
+                    MenuItem::button(MenuId::RestartStage, "Restart Stage")
+                        .enabled(!world.mode().is_competitive()),
+                    MenuItem::button(MenuId::Open(MenuPage::Settings), "Settings"),
+                    MenuItem::button(MenuId::Open(MenuPage::ControlsHelp), "Controls"),
+                    MenuItem::button(MenuId::QuitToMenu, "Quit to Menu"),
+                ],
+            ),
+            MenuPage::Modes => Menu::new(
+                Vec2::new(center, 480.0),
+                vec![
+                    MenuItem::choice(
+                        MenuId::Players,
+                        "Players",
+                        &["1", "2"],
+                        settings.players - 1,
+                    ),
+                    MenuItem::button(MenuId::Start(GameMode::Classic), "Classic"),
+                    MenuItem::button(MenuId::Start(GameMode::Campaign), "Campaign"),
+                    MenuItem::button(MenuId::Start(GameMode::Endless), "Endless"),
+                    MenuItem::button(MenuId::Start(GameMode::Daily), "Daily Challenge")
+                        .enabled(!world.daily().attempted_today()),
+                    MenuItem::button(MenuId::Start(GameMode::TimeAttack), "Time Attack"),
+                    MenuItem::button(MenuId::Start(GameMode::ScoreAttack), "Score Attack"),
+                    MenuItem::button(MenuId::Back, "Back"),
+                ],
+            ),
+            MenuPage::Settings => Menu::new(
+                top,
+                vec![
+                    MenuItem::button(MenuId::Open(MenuPage::Video), "Video"),
+                    MenuItem::button(MenuId::Open(MenuPage::Audio), "Audio"),
+                    MenuItem::button(MenuId::Open(MenuPage::Controls), "Controls"),
+                    MenuItem::button(
+                        MenuId::Open(MenuPage::PlayerTwoControls),
+                        "Player 2 controls",
+                    ),
+                    MenuItem::button(MenuId::Open(MenuPage::Gameplay), "Gameplay"),
+                    MenuItem::button(MenuId::Open(MenuPage::Accessibility), "Accessibility"),
+                    MenuItem::button(MenuId::Back, "Back"),
+                ],
+            ),
+            MenuPage::Video => {
+                let sizes: Vec<_> = WINDOW_SIZES
+                    .iter()
+                    .map(|(w, h)| format!("{}x{}", w, h))
+                    .collect();
+                let sizes: Vec<_> = sizes.iter().map(String::as_str).collect();
+                let size = WINDOW_SIZES
+                    .iter()
+                    .position(|&size| size == settings.window_size)
+                    .unwrap_or(WINDOW_SIZES.len() - 1);
+
+                Menu::new(
+                    top,
+                    vec![
+                        MenuItem::toggle(MenuId::Fullscreen, "Fullscreen", settings.fullscreen),
+                        MenuItem::choice(MenuId::WindowSize, "Window size", &sizes, size),
+                        MenuItem::toggle(MenuId::Vsync, "VSync (on restart)", settings.vsync),
+                        MenuItem::toggle(MenuId::ShowFps, "Show FPS", settings.show_fps),
+                        MenuItem::toggle(MenuId::Minimap, "Minimap", settings.show_minimap),
+                        MenuItem::button(MenuId::Back, "Back"),
+                    ],
+                )
+            }
+            MenuPage::Audio => Menu::new(
+                top,
+                vec![
+                    MenuItem::slider(
+                        MenuId::MasterVolume,
+                        "Master volume",
+                        settings.mixer.master,
+                        0.1,
+                    ),
+                    MenuItem::slider(
+                        MenuId::MusicVolume,
+                        "Music volume",
+                        settings.mixer.music,
+                        0.1,
+                    ),
+                    MenuItem::slider(MenuId::SfxVolume, "Sound effects", settings.mixer.sfx, 0.1),
+                    MenuItem::button(MenuId::Back, "Back"),
+                ],
+            ),
+            MenuPage::Controls | MenuPage::PlayerTwoControls => {
+                let (player, actions) = self.bindings();
+                let controls = settings.player_controls(player);
+                let mut items: Vec<_> = actions
+                    .iter()
+                    .map(|&action| {
+                        MenuItem::button(MenuId::Binding(action), &binding_label(action, &controls))
+                    })
+                    .collect();
+                items.push(MenuItem::button(MenuId::ResetControls, "Reset to defaults"));
+                items.push(MenuItem::button(MenuId::Back, "Back"));
+                Menu::new(Vec2::new(center, 280.0), items)
+            }
+            MenuPage::Gameplay => {
+                let names: Vec<_> = Difficulty::ALL.iter().map(|d| d.name()).collect();
+                let difficulty = Difficulty::ALL
+                    .iter()
+                    .position(|&d| d == settings.difficulty)
+                    .unwrap_or(0);
+                // presets show their numbers, but only Custom can change them
+                let custom = settings.difficulty == Difficulty::Custom;
+                let tuning = settings.tuning();
+                let (min_factor, max_factor) = FACTOR_RANGE;
+                let hp = |id, label, hp| {
+                    MenuItem::range_slider(
+                        id,
+                        label,
+                        hp as f32,
+                        HP_RANGE.0 as f32,
+                        HP_RANGE.1 as f32,
+                        1.0,
+                    )
+                    .enabled(custom)
+                };
+
+                Menu::new(
+                    Vec2::new(center, 340.0),
+                    vec![
+                        MenuItem::choice(MenuId::Difficulty, "Difficulty", &names, difficulty),
+                        MenuItem::range_slider(
+                            MenuId::HornetSpeed,
+                            "Hornet speed",
+                            tuning.speed,
+                            min_factor,
+                            max_factor,
+                            0.05,
+                        )
+                        .enabled(custom),
+                        MenuItem::range_slider(
+                            MenuId::HornetCount,
+                            "Hornet count",
+                            tuning.count,
+                            min_factor,
+                            max_factor,
+                            0.05,
+                        )
+                        .enabled(custom),
+                        hp(MenuId::BeeHp, "Bee HP", tuning.player_hp),
+                        hp(MenuId::HiveHp, "Hive HP", tuning.hive_hp),
+                        MenuItem::range_slider(
+                            MenuId::StingDamage,
+                            "Sting damage",
+                            tuning.damage as f32,
+                            DAMAGE_RANGE.0 as f32,
+                            DAMAGE_RANGE.1 as f32,
+                            1.0,
+                        )
+                        .enabled(custom),
+                        MenuItem::toggle(
+                            MenuId::AdaptiveDifficulty,
+                            "Adaptive difficulty",
+                            settings.adaptive_difficulty,
+                        ),
+                        MenuItem::button(MenuId::Back, "Back"),
+                    ],
+                )
+            }
+            MenuPage::Accessibility => {
+                let names: Vec<_> = EffectsLevel::ALL.iter().map(|l| l.name()).collect();
+                let effects = EffectsLevel::ALL
+                    .iter()
+                    .position(|&level| level == settings.effects)
+                    .unwrap_or(0);
+
+                Menu::new(
+                    top,
+                    vec![
+                        MenuItem::choice(MenuId::ScreenEffects, "Screen effects", &names, effects),
+                        MenuItem::toggle(MenuId::HoldToFire, "Hold to fire", settings.hold_to_fire),
+                        MenuItem::button(MenuId::Back, "Back"),
+                    ],
+                )
+            }
+            MenuPage::HighScores => {
+                let names: Vec<_> = BOARDS.iter().map(|(name, _)| *name).collect();
+                Menu::new(
+                    Vec2::new(center, VIRTUAL_HEIGHT - 150.0),
+                    vec![
+                        MenuItem::choice(MenuId::Board, "Board", &names, 0),
+                        MenuItem::button(MenuId::Back, "Back"),
+                    ],
+                )
+            }
+            MenuPage::Achievements | MenuPage::ControlsHelp => {
+                Menu::new(back, vec![MenuItem::button(MenuId::Back, "Back")])
+            }
+        }
+    }
+
+    /// Reads a settings page's widgets back into `settings`.
+    pub fn apply(self, menu: &Menu, settings: &mut Settings) {
+        let item = |id| menu.item(id);
+        match self {
+            MenuPage::Video => {
+                settings.fullscreen = item(MenuId::Fullscreen).is_on();
+                settings.window_size = WINDOW_SIZES[item(MenuId::WindowSize).selected()];
+                settings.vsync = item(MenuId::Vsync).is_on();
+                settings.show_fps = item(MenuId::ShowFps).is_on();
+                settings.show_minimap = item(MenuId::Minimap).is_on();
+            }
+            MenuPage::Audio => {
+                settings.mixer.master = item(MenuId::MasterVolume).value();
+                settings.mixer.music = item(MenuId::MusicVolume).value();
+                settings.mixer.sfx = item(MenuId::SfxVolume).value();
+            }
+            MenuPage::Gameplay => {
+                let difficulty = Difficulty::ALL[item(MenuId::Difficulty).selected()];
+                // until Custom is picked the sliders hold a preset's numbers,
+                // which shouldn't overwrite the custom ones
+                if difficulty == Difficulty::Custom && settings.difficulty == Difficulty::Custom {
+                    settings.custom_difficulty = Tuning {
+                        speed: item(MenuId::HornetSpeed).value(),
+                        count: item(MenuId::HornetCount).value(),
+                        player_hp: item(MenuId::BeeHp).value().round() as i32,
+                        hive_hp: item(MenuId::HiveHp).value().round() as i32,
+                        damage: item(MenuId::StingDamage).value().round() as i32,
+                    };
+                }
+                settings.difficulty = difficulty;
+                settings.adaptive_difficulty = item(MenuId::AdaptiveDifficulty).is_on();
+            }
+            MenuPage::Modes => settings.players = item(MenuId::Players).selected() + 1,
+            MenuPage::Accessibility => {
+                settings.effects = EffectsLevel::ALL[item(MenuId::ScreenEffects).selected()];
+                settings.hold_to_fire = item(MenuId::HoldToFire).is_on();
+            }
+            _ => {}
+        }
+    }
+}
+
+pub fn binding_label(action: Action, controls: &Controls) -> String {
+    format!("{}: {}", action.label(), key_name(controls.key(action)))
+}
@@ -0,0 +1,220 @@
+use super::*;
+
+use std::f32::consts::{PI, TAU};
+
+/// Hard cap on live particles, new ones are dropped once the pool is full.
+const MAX_PARTICLES: usize = 2000;
+
+/// Describes a burst of particles; the presets below cover the game's effects.
+#[derive(Debug, Clone, Copy)]
+pub struct Emitter {
+    pub count: usize,
+    /// Seconds, min and max.
+    pub lifetime: (f32, f32),
+    /// Units per second, min and max.
+    pub speed: (f32, f32),
+    /// Radians around the emission direction particles may head off in.
+    pub spread: f32,
+    pub size: (f32, f32),
+    /// Colors interpolated between over each particle's lifetime.
+    pub ramp: &'static [Color],
+    /// Units per second squared.
+    pub gravity: Vec2,
+    /// Fraction of velocity lost per second.
+    pub drag: f32,
+}
+
+pub const STINGER_IMPACT: Emitter = Emitter {
+    count: 8,
+    lifetime: (0.1, 0.3),
+    speed: (150.0, 400.0),
+    spread: PI / 2.0,
+    size: (2.0, 4.0),
+    ramp: &[WHITE, Color::new(1.0, 1.0, 1.0, 0.0)],
+    gravity: Vec2::ZERO,
+    drag: 4.0,
+};
+
+pub const HORNET_DEATH: Emitter = Emitter {
+    count: 30,
+    lifetime: (0.4, 0.9),
+    speed: (50.0, 300.0),
+    spread: TAU,
+    size: (3.0, 8.0),
+    ramp: &[ORANGE, RED, Color::new(0.1, 0.1, 0.1, 0.0)],
+    gravity: Vec2::ZERO,
+    drag: 3.0,
+};
+
+pub const HIVE_SPLINTERS: Emitter = Emitter {
+    count: 16,
+    lifetime: (0.5, 1.0),
+    speed: (100.0, 350.0),
+    spread: PI,
+    size: (3.0, 6.0),
+    ramp: &[GOLD, BROWN, Color::new(0.3, 0.2, 0.1, 0.0)],
+    gravity: Vec2::new(0.0, 600.0),
+    drag: 1.0,
+};
+
+pub const HONEY_DRIP: Emitter = Emitter {
+    count: 1,
+    lifetime: (0.6, 1.2),
+    speed: (0.0, 20.0),
+    spread: PI / 4.0,
+    size: (3.0, 5.0),
+    ramp: &[GOLD, Color::new(0.9, 0.6, 0.0, 0.0)],
+    gravity: Vec2::new(0.0, 200.0),
+    drag: 0.5,
+};
+
+pub const POLLEN_TRAIL: Emitter = Emitter {
+    count: 1,
+    lifetime: (0.3, 0.6),
+    speed: (10.0, 40.0),
+    spread: PI / 3.0,
+    size: (2.0, 3.5),
+    ramp: &[YELLOW, Color::new(1.0, 1.0, 0.6, 0.0)],
+    gravity: Vec2::ZERO,
+    drag: 2.0,
+};
+
+#[derive(Debug, Clone, Copy)]
+struct Particle {
+    pos: Vec2,
+    velocity: Vec2,
+    age: f32,
+    lifetime: f32,
+    size: f32,
+    gravity: Vec2,
+    drag: f32,
+    ramp: &'static [Color],
+}
+
+impl Particle {
+    fn color(&self) -> Color {
+        let last = self.ramp.len() - 1;
+        if last == 0 {
+            return self.ramp[0];
+        }
+
+        let t = (self.age / self.lifetime).clamp(0.0, 1.0) * last as f32;
+        let i = (t as usize).min(last - 1);
+        lerp_color(self.ramp[i], self.ramp[i + 1], t - i as f32)
+    }
+}
+
+#[derive(Debug)]
+pub struct ParticleSystem {
+    particles: Vec<Particle>,
+}
+
+impl ParticleSystem {
+    /// Emits `emitter.count` particles at `pos`, heading off around `angle`.
+    pub fn emit(&mut self, emitter: &Emitter, pos: Vec2, angle: f32) {
+        for _ in 0..emitter.count {
+            if self.particles.len() >= MAX_PARTICLES {
+                return;
+            }
+
+            let heading = angle + rand::gen_range(-emitter.spread, emitter.spread) / 2.0;
+            let speed = rand::gen_range(emitter.speed.0, emitter.speed.1);
+
+            self.particles.push(Particle {
+                pos,
+                velocity: Vec2::new(heading.cos(), heading.sin()) * speed,
+                age: 0.0,
+                lifetime: rand::gen_range(emitter.lifetime.0, emitter.lifetime.1),
+                size: rand::gen_range(emitter.size.0, emitter.size.1),
+                gravity: emitter.gravity,
+                drag: emitter.drag,
+                ramp: emitter.ramp,
+            });
+        }
+    }
+
+    /// Emits in every direction at once.
+    pub fn burst(&mut self, emitter: &Emitter, pos: Vec2) {
+        self.emit(
+            &Emitter {
+                spread: TAU,
+                ..*emitter
+            },
+            pos,
+            0.0,
+        );
+    }
+
+    pub fn tick(&mut self, dt: f32) {
+        let mut i = 0;
+        while i < self.particles.len() {
+            let p = &mut self.particles[i];
+            p.age += dt;
+            if p.age >= p.lifetime {
+                self.particles.swap_remove(i);
+                continue;
+            }
+
+            p.velocity += p.gravity * dt;
+            p.velocity *= (1.0 - p.drag * dt).max(0.0);
+            p.pos += p.velocity * dt;
+            i += 1;
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.particles.clear();
+    }
+
+    pub fn handle(&mut self, event: &GameEvent) {
+        // sparks fly back the way the stinger came
+        let splash = |direction: Direction| {
+            let back = -direction.unit_vec();
+            back.y.atan2(back.x)
+        };
+
+        match *event {
+            GameEvent::EnemyHit { pos, direction, .. }
+            | GameEvent::ShotBlocked { pos, direction } => {
+                self.emit(&STINGER_IMPACT, pos, splash(direction));
+            }
+            GameEvent::EnemyKilled { pos, .. } | GameEvent::EnemyStung { pos } => {
+                self.burst(&HORNET_DEATH, pos);
+            }
+            GameEvent::HiveDamaged { pos, .. } => {
+                self.emit(&HIVE_SPLINTERS, pos, -PI / 2.0);
+            }
+            GameEvent::HiveDestroyed { pos } => {
+                for _ in 0..3 {
+                    self.burst(&HIVE_SPLINTERS, pos);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+impl Default for ParticleSystem {
+    fn default() -> Self {
+        ParticleSystem {
+            particles: Vec::with_capacity(MAX_PARTICLES),
+        }
+    }
+}
+
+impl Drawable for ParticleSystem {
+    fn draw(&self) {
+        for p in &self.particles {
+            draw_circle(p.pos.x, p.pos.y, p.size, p.color());
+        }
+    }
+}
+
+fn lerp_color(a: Color, b: Color, t: f32) -> Color {
+    Color::new(
+        a.r + (b.r - a.r) * t,
+        a.g + (b.g - a.g) * t,
+        a.b + (b.b - a.b) * t,
+        a.a + (b.a - a.a) * t,
+    )
+}
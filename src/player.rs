@@ -1,5 +1,14 @@
 use super::*;
 
+/// Bees in play at once, sharing the keyboard.
+pub const MAX_PLAYERS: usize = 2;
+/// Each bee's body colour, in player order.
+pub const PLAYER_COLORS: [Color; MAX_PLAYERS] = [YELLOW, ORANGE];
+/// How close a bee has to stay to a downed one to revive it.
+pub const REVIVE_RADIUS: f32 = 120.0;
+/// How long reviving takes.
+pub const REVIVE_SECS: f32 = 3.0;
+
 #[derive(Debug)]
 pub struct Player {
     pub hp: i32,
@@ -12,10 +21,14 @@ pub struct Player {
     pub damage: u32,
     pub state: PlayerState,
     pub direction: Direction,
+    pub wings: Animation,
+    pub color: Color,
 }
 
 impl Actor for Player {
     fn tick(&mut self) {
+        self.wings.tick(get_frame_time());
+
         if let PlayerState::Invulnerable(until) = self.state {
             if until < get_time() {
                 self.state = PlayerState::Ok;
@@ -51,44 +64,80 @@ impl Mobile for Player {
 }
 
 impl Player {
-    pub fn draw_hp(&self) {
-        draw_right_aligned_text(
-            &format!("HP: {} / {}", self.hp, self.max_hp),
-            screen_width() - 20.0,
-            50.0,
-            50,
-            LIGHTGRAY,
-        );
+    /// The bee's HP panel at `y`, headed with `label` when there's more than
+    /// one bee to tell apart.
+    pub fn draw_hp(&self, label: &str, y: f32, color: Color) {
+        let (text, color) = match self.state {
+            PlayerState::Down(_) => (format!("{}Down!", label), RED),
+            _ => (format!("{}HP: {} / {}", label, self.hp, self.max_hp), color),
+        };
+        draw_right_aligned_text(&text, VIRTUAL_WIDTH - 20.0, y, 50, color);
+    }
+
+    pub fn is_down(&self) -> bool {
+        matches!(self.state, PlayerState::Down(_))
+    }
+
+    /// Stings taken from `damage`, leaving the bee down once out of HP.
+    /// Returns whether that downed it.
+    pub fn sting(&mut self, damage: i32) -> bool {
+        self.hp -= damage;
+        if self.hp <= 0 {
+            self.state = PlayerState::Down(0.0);
+            true
+        } else {
+            self.state = PlayerState::Invulnerable(get_time() + 1.0);
+            false
+        }
+    }
+
+    /// Adds `dt` to the time spent reviving a downed bee, bringing it back
+    /// with half its HP once that's long enough. Returns whether it did.
+    pub fn revive(&mut self, dt: f32) -> bool {
+        let progress = match self.state {
+            PlayerState::Down(progress) => progress + dt,
+            _ => return false,
+        };
+        if progress < REVIVE_SECS {
+            self.state = PlayerState::Down(progress);
+            return false;
+        }
+
+        self.hp = (self.max_hp + 1) / 2;
+        self.state = PlayerState::Invulnerable(get_time() + 2.0);
+        true
     }
 
-    pub fn handle_input(&mut self) -> Vec2 {
+    pub fn handle_input(&mut self, controls: &Controls) -> Vec2 {
+        let speed = self.speed * frame_steps();
         let mut dx = 0.0;
         let mut dy = 0.0;
-        if is_key_down(KeyCode::W) {
-            dy -= self.speed;
+        if controls.is_down(Action::MoveUp) {
+            dy -= speed;
             self.direction = Direction::Up;
         }
-        if is_key_down(KeyCode::S) {
-            dy += self.speed;
+        if controls.is_down(Action::MoveDown) {
+            dy += speed;
             self.direction = Direction::Down;
         }
-        if is_key_down(KeyCode::D) {
-            dx += self.speed;
+        if controls.is_down(Action::MoveRight) {
+            dx += speed;
             self.direction = Direction::Right;
         }
-        if is_key_down(KeyCode::A) {
-            dx -= self.speed;
+        if controls.is_down(Action::MoveLeft) {
+            dx -= speed;
             self.direction = Direction::Left;
         }
 
         Vec2::new(dx, dy)
     }
 
-    pub fn shoot(&self, direction: Direction) -> Projectile {
+    pub fn shoot(&self, direction: Direction, owner: usize) -> Projectile {
         let projectile_speed = 10.0;
 
         Projectile {
             active: true,
+            owner,
             x: self.x,
             y: self.y,
             radius: 20.0,
@@ -101,16 +150,45 @@ impl Player {
 
 impl Drawable for Player {
     fn draw(&self) {
-        draw_circle(self.x, self.y, self.radius, self.state.into());
+        let color = match self.state {
+            PlayerState::Ok => self.color,
+            PlayerState::Invulnerable(_) => GRAY,
+            PlayerState::Down(_) => DARKGRAY,
+        };
+        self.draw_body(color);
+
+        if let PlayerState::Down(progress) = self.state {
+            let width = self.radius * 3.0;
+            let x = self.x - width / 2.0;
+            let y = self.y + self.radius * 2.0;
+            draw_rectangle(x, y, width, 10.0, DARKGRAY);
+            draw_rectangle(x, y, width * progress / REVIVE_SECS, 10.0, self.color);
+        }
+    }
+}
+
+impl Player {
+    pub fn draw_body(&self, color: Color) {
+        // the sprite is drawn yellow already
+        let tint = if color == YELLOW { WHITE } else { color };
+        if draw_animated_sprite(
+            BEE,
+            &self.wings,
+            self.pos(),
+            Vec2::splat(self.radius * 3.2),
+            self.direction.angle(),
+            tint,
+        ) {
+            return;
+        }
+
+        let wing = self.radius * 0.5 * wing_scale(&self.wings);
+
+        draw_circle(self.x, self.y, self.radius, color);
         match self.direction {
             Direction::Up => {
                 // head
-                draw_circle(
-                    self.x,
-                    self.y - self.radius,
-                    self.radius * 0.6,
-                    self.state.into(),
-                );
+                draw_circle(self.x, self.y - self.radius, self.radius * 0.6, color);
 
                 // stripe
                 draw_rectangle(
@@ -122,17 +200,12 @@ impl Drawable for Player {
                 );
 
                 // wings
-                draw_circle(self.x - self.radius, self.y, self.radius * 0.5, WHITE);
-                draw_circle(self.x + self.radius, self.y, self.radius * 0.5, WHITE);
+                draw_circle(self.x - self.radius, self.y, wing, WHITE);
+                draw_circle(self.x + self.radius, self.y, wing, WHITE);
             }
             Direction::Down => {
                 // head
-                draw_circle(
-                    self.x,
-                    self.y + self.radius,
-                    self.radius * 0.6,
-                    self.state.into(),
-                );
+                draw_circle(self.x, self.y + self.radius, self.radius * 0.6, color);
 
                 // stripe
                 draw_rectangle(
@@ -144,17 +217,12 @@ impl Drawable for Player {
                 );
 
                 // wings
-                draw_circle(self.x - self.radius, self.y, self.radius * 0.5, WHITE);
-                draw_circle(self.x + self.radius, self.y, self.radius * 0.5, WHITE);
+                draw_circle(self.x - self.radius, self.y, wing, WHITE);
+                draw_circle(self.x + self.radius, self.y, wing, WHITE);
             }
             Direction::Left => {
                 // head
-                draw_circle(
-                    self.x - self.radius,
-                    self.y,
-                    self.radius * 0.6,
-                    self.state.into(),
-                );
+                draw_circle(self.x - self.radius, self.y, self.radius * 0.6, color);
 
                 // stripe
                 draw_rectangle(
@@ -166,17 +234,12 @@ impl Drawable for Player {
                 );
 
                 // wings
-                draw_circle(self.x, self.y - self.radius, self.radius * 0.5, WHITE);
-                draw_circle(self.x, self.y + self.radius, self.radius * 0.5, WHITE);
+                draw_circle(self.x, self.y - self.radius, wing, WHITE);
+                draw_circle(self.x, self.y + self.radius, wing, WHITE);
             }
             Direction::Right => {
                 // head
-                draw_circle(
-                    self.x + self.radius,
-                    self.y,
-                    self.radius * 0.6,
-                    self.state.into(),
-                );
+                draw_circle(self.x + self.radius, self.y, self.radius * 0.6, color);
 
                 // stripe
                 draw_rectangle(
@@ -188,13 +251,23 @@ impl Drawable for Player {
                 );
 
                 // wings
-                draw_circle(self.x, self.y - self.radius, self.radius * 0.5, WHITE);
-                draw_circle(self.x, self.y + self.radius, self.radius * 0.5, WHITE);
+                draw_circle(self.x, self.y - self.radius, wing, WHITE);
+                draw_circle(self.x, self.y + self.radius, wing, WHITE);
             }
         }
     }
 }
 
+impl Player {
+    pub fn at(pos: Vec2) -> Self {
+        Player {
+            x: pos.x,
+            y: pos.y,
+            ..Default::default()
+        }
+    }
+}
+
 impl Default for Player {
     fn default() -> Self {
         let radius = 25.0;
@@ -204,14 +277,16 @@ impl Default for Player {
         Player {
             hp: max_hp,
             max_hp,
-            x: screen_width() / 2.0,
-            y: screen_height() / 2.0,
+            x: 0.0,
+            y: 0.0,
             radius,
             speed,
             damage: 25,
             max_projectiles: 20,
             state: PlayerState::Ok,
             direction: Direction::Up,
+            wings: Animation::new(WING_FLAP),
+            color: YELLOW,
         }
     }
 }
@@ -220,20 +295,16 @@ impl Default for Player {
 pub enum PlayerState {
     Ok,
     Invulnerable(f64),
-}
-
-impl From<PlayerState> for Color {
-    fn from(state: PlayerState) -> Color {
-        match state {
-            PlayerState::Ok => YELLOW,
-            PlayerState::Invulnerable(_) => GRAY,
-        }
-    }
+    /// Out of HP and out of play until another bee revives it; holds the
+    /// seconds spent reviving so far.
+    Down(f32),
 }
 
 #[derive(Debug)]
 pub struct Projectile {
     pub active: bool,
+    /// Index of the bee that shot it.
+    pub owner: usize,
     pub x: f32,
     pub y: f32,
     pub radius: f32,
@@ -244,8 +315,9 @@ pub struct Projectile {
 
 impl Actor for Projectile {
     fn tick(&mut self) {
-        self.x += self.velocity[0];
-        self.y += self.velocity[1];
+        let step = self.velocity * frame_steps();
+        self.x += step.x;
+        self.y += step.y;
     }
 
     fn bounding_box(&self) -> Rect {
@@ -259,8 +331,27 @@ impl Actor for Projectile {
     }
 }
 
+impl Positioned for Projectile {
+    fn x(&self) -> f32 {
+        self.x
+    }
+    fn y(&self) -> f32 {
+        self.y
+    }
+}
+
 impl Drawable for Projectile {
     fn draw(&self) {
+        if draw_sprite(
+            STINGER,
+            self.pos(),
+            Vec2::new(self.radius, self.radius * 2.0),
+            self.direction.angle(),
+            WHITE,
+        ) {
+            return;
+        }
+
         match self.direction {
             Direction::Up => {
                 draw_triangle(
@@ -0,0 +1,49 @@
+/// A seedable PCG32 generator for whatever decides how a stage plays out,
+/// kept apart from the global `rand` that particles and screen shake draw on,
+/// so the same seed always lays out, spawns and targets the same.
+#[derive(Debug, Clone)]
+pub struct Rng {
+    state: u64,
+}
+
+const MULTIPLIER: u64 = 6_364_136_223_846_793_005;
+const INCREMENT: u64 = 1_442_695_040_888_963_407;
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        // splitmix64, so neighbouring seeds start far apart
+        let mut z = seed.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+
+        Rng {
+            state: z ^ (z >> 31),
+        }
+    }
+
+    pub fn next_u32(&mut self) -> u32 {
+        let old = self.state;
+        self.state = old.wrapping_mul(MULTIPLIER).wrapping_add(INCREMENT);
+        let xorshifted = (((old >> 18) ^ old) >> 27) as u32;
+        xorshifted.rotate_right((old >> 59) as u32)
+    }
+
+    /// From `low` up to but not including `high`.
+    pub fn gen_range(&mut self, low: f32, high: f32) -> f32 {
+        let unit = self.next_u32() as f64 / (u32::MAX as f64 + 1.0);
+        (low as f64 + (high as f64 - low as f64) * unit) as f32
+    }
+
+    /// From 0 up to but not including `n`.
+    pub fn below(&mut self, n: usize) -> usize {
+        ((self.next_u32() as u64 * n as u64) >> 32) as usize
+    }
+
+    pub fn choose<'a, T>(&mut self, items: &'a [T]) -> Option<&'a T> {
+        if items.is_empty() {
+            None
+        } else {
+            Some(&items[self.below(items.len())])
+        }
+    }
+}
@@ -0,0 +1,655 @@
+use super::*;
+
+/// What a scene wants done with the stack after its update.
+pub enum Transition {
+    None,
+    Push(Box<dyn Scene>),
+    Pop,
+    /// Swaps the top scene for another.
+    Replace(Box<dyn Scene>),
+    /// Clears the stack down to a new root.
+    Reset(Box<dyn Scene>),
+    Quit,
+}
+
+/// One screen of the game. Only the top scene of the stack is updated, but
+/// overlays are drawn over whatever is beneath them, which stays frozen.
+pub trait Scene: std::fmt::Debug {
+    fn update(&mut self, world: &mut World) -> Transition;
+
+    /// Drawn in HUD space, over the scene below if this is an overlay.
+    fn draw(&self, world: &World);
+
+    fn is_overlay(&self) -> bool {
+        false
+    }
+
+    /// Called instead of `update` on a frame the window lost focus, which on
+    /// native only means it went unshown for a few seconds (see `focus_lost`).
+    fn focus_lost(&mut self, _world: &mut World) -> Transition {
+        Transition::None
+    }
+
+    /// `None` keeps whatever the scene below is playing.
+    fn music(&self) -> Option<MusicTrack> {
+        None
+    }
+
+    /// Whether hornets are live, so their drone should be heard.
+    fn is_gameplay(&self) -> bool {
+        false
+    }
+}
+
+#[derive(Debug)]
+pub struct SceneStack {
+    scenes: Vec<Box<dyn Scene>>,
+}
+
+impl SceneStack {
+    pub fn new(root: Box<dyn Scene>) -> Self {
+        SceneStack { scenes: vec![root] }
+    }
+
+    /// Once empty, the game is over and done with.
+    pub fn is_empty(&self) -> bool {
+        self.scenes.is_empty()
+    }
+
+    pub fn update(&mut self, world: &mut World) {
+        // checked every frame so a switch made on another screen isn't held
+        // against the next stage
+        let focus_lost = focus_lost();
+
+        if let Some(scene) = self.scenes.last_mut() {
+            let mut transition = Transition::None;
+            if focus_lost {
+                transition = scene.focus_lost(world);
+            }
+            if let Transition::None = transition {
+                transition = scene.update(world);
+            }
+            self.apply(transition);
+        }
+
+        let music = self
+            .scenes
+            .iter()
+            .rev()
+            .find_map(|scene| scene.music())
+            .unwrap_or(MusicTrack::Title);
+        let gameplay = matches!(self.scenes.last(), Some(scene) if scene.is_gameplay());
+        world.end_frame(music, gameplay);
+    }
+
+    fn apply(&mut self, transition: Transition) {
+        match transition {
+            Transition::None => {}
+            Transition::Push(scene) => self.scenes.push(scene),
+            Transition::Pop => {
+                self.scenes.pop();
+            }
+            Transition::Replace(scene) => {
+                self.scenes.pop();
+                self.scenes.push(scene);
+            }
+            Transition::Reset(scene) => {
+                self.scenes.clear();
+                self.scenes.push(scene);
+            }
+            Transition::Quit => self.scenes.clear(),
+        }
+    }
+
+    /// Draws from the topmost scene that isn't an overlay upwards.
+    pub fn draw(&self, world: &World) {
+        let first = self
+            .scenes
+            .iter()
+            .rposition(|scene| !scene.is_overlay())
+            .unwrap_or(0);
+        for scene in &self.scenes[first..] {
+            scene.draw(world);
+        }
+
+        world.achievements().draw_toast();
+        set_default_camera();
+    }
+}
+
+fn dim_background() {
+    draw_rectangle(
+        0.0,
+        0.0,
+        VIRTUAL_WIDTH,
+        VIRTUAL_HEIGHT,
+        Color::new(0.0, 0.0, 0.0, 0.5),
+    );
+}
+
+/// A stage being played.
+#[derive(Debug)]
+pub struct GameScene;
+
+impl GameScene {
+    fn pause(world: &World) -> Transition {
+        Transition::Push(Box::new(MenuScene::new(MenuPage::Pause, world)))
+    }
+}
+
+impl Scene for GameScene {
+    fn update(&mut self, world: &mut World) -> Transition {
+        if world.settings().controls.is_pressed(Action::Pause) {
+            return GameScene::pause(world);
+        }
+
+        match world.step() {
+            Some(StageOutcome::Cleared) => Transition::Push(Box::new(ShopScene)),
+            Some(StageOutcome::Lost) => Transition::Push(Box::new(SummaryScene::new(world, false))),
+            Some(StageOutcome::Finished) => {
+                Transition::Push(Box::new(SummaryScene::new(world, true)))
+            }
+            None => Transition::None,
+        }
+    }
+
+    fn draw(&self, world: &World) {
+        world.draw();
+    }
+
+    fn focus_lost(&mut self, world: &mut World) -> Transition {
+        GameScene::pause(world)
+    }
+
+    fn music(&self) -> Option<MusicTrack> {
+        Some(MusicTrack::Stage)
+    }
+
+    fn is_gameplay(&self) -> bool {
+        true
+    }
+}
+
+/// A page of the main or pause menus. The pause menu is drawn over the frozen
+/// stage; every other page is drawn over the bare arena.
+#[derive(Debug)]
+pub struct MenuScene {
+    page: MenuPage,
+    menu: Menu,
+    /// The action waiting on a key press to bind.
+    rebinding: Option<Action>,
+}
+
+impl MenuScene {
+    pub fn new(page: MenuPage, world: &World) -> Self {
+        MenuScene {
+            page,
+            menu: page.menu(world),
+            rebinding: None,
+        }
+    }
+
+    fn open(page: MenuPage, world: &World) -> Transition {
+        Transition::Push(Box::new(MenuScene::new(page, world)))
+    }
+
+    /// Rebuilds the page from the settings, keeping the selection.
+    fn refresh(&mut self, world: &World) {
+        let selected = self.menu.selected;
+        self.menu = self.page.menu(world);
+        self.menu.selected = selected;
+    }
+
+    /// Binds whatever key is pressed next to `action`, or keeps waiting if
+    /// it can't be; Backspace keeps the old binding.
+    fn rebind(&mut self, action: Action, world: &mut World) {
+        let (player, _) = self.page.bindings();
+        if !is_key_pressed(KeyCode::Backspace) {
+            match get_last_key_pressed() {
+                Some(key) if is_bindable(key) => {
+                    let mut settings = *world.settings();
+                    if !settings.bind(player, action, key) {
+                        self.menu.item_mut(MenuId::Binding(action)).label =
+                            format!("{}: {} is taken", action.label(), key_name(key));
+                        return;
+                    }
+                    world.apply_settings(settings);
+                }
+                _ => return,
+            }
+        }
+
+        // a swap changes another row too
+        self.refresh(world);
+        self.rebinding = None;
+    }
+}
+
+impl Scene for MenuScene {
+    fn update(&mut self, world: &mut World) -> Transition {
+        if let Some(action) = self.rebinding {
+            self.rebind(action, world);
+            return Transition::None;
+        }
+
+        let event = match self.menu.update() {
+            Some(event) => event,
+            None => return Transition::None,
+        };
+        let id = match event {
+            MenuEvent::Activated(id) | MenuEvent::Changed(id) => id,
+            MenuEvent::Back => MenuId::Back,
+        };
+
+        match id {
+            MenuId::NewGame => {
+                world.start(GameMode::Classic);
+                Transition::Replace(Box::new(GameScene))
+            }
+            MenuId::Continue => Transition::Replace(Box::new(GameScene)),
+            MenuId::Quit => Transition::Quit,
+            // the main menu is the bottom of the stack
+            MenuId::Back if self.page == MenuPage::Main => Transition::None,
+            MenuId::Resume | MenuId::Back => Transition::Pop,
+            MenuId::RestartStage => {
+                world.restart_stage();
+                Transition::Pop
+            }
+            MenuId::QuitToMenu => {
+                Transition::Reset(Box::new(MenuScene::new(MenuPage::Main, world)))
+            }
+            MenuId::Open(page) => MenuScene::open(page, world),
+            MenuId::Start(mode) => {
+                world.start(mode);
+                Transition::Reset(Box::new(GameScene))
+            }
+            MenuId::Board => Transition::None,
+            MenuId::ResetControls => {
+                let (player, _) = self.page.bindings();
+                let mut settings = *world.settings();
+                settings.reset_controls(player);
+                world.apply_settings(settings);
+                self.refresh(world);
+                Transition::None
+            }
+            MenuId::Binding(action) => {
+                self.menu.item_mut(id).label = format!("{}: ...", action.label());
+                self.rebinding = Some(action);
+                Transition::None
+            }
+            _ => {
+                let page = self.page;
+                let mut settings = *world.settings();
+                page.apply(&self.menu, &mut settings);
+                world.apply_settings(settings);
+                // a new difficulty changes what the sliders show and whether
+                // they can be moved
+                if page == MenuPage::Gameplay {
+                    self.refresh(world);
+                }
+                Transition::None
+            }
+        }
+    }
+
+    fn draw(&self, world: &World) {
+        if !self.is_overlay() {
+            world.draw_arena();
+        }
+        dim_background();
+
+        match self.page {
+            MenuPage::Main | MenuPage::Pause => {
+                draw_centered_text(self.page.title(), VIRTUAL_WIDTH / 2.0, 300.0, 100, WHITE);
+            }
+            MenuPage::Modes => {
+                draw_centered_text(self.page.title(), VIRTUAL_WIDTH / 2.0, 300.0, 100, WHITE);
+                if let Some(result) = world.daily().result() {
+                    draw_centered_text(&result, VIRTUAL_WIDTH / 2.0, 390.0, 40, GOLD);
+                }
+            }
+            MenuPage::ControlsHelp => {
+                draw_centered_text(self.page.title(), VIRTUAL_WIDTH / 2.0, 150.0, 80, WHITE);
+                for (i, action) in Action::ALL.iter().copied().enumerate() {
+                    let y = 280.0 + 65.0 * i as f32;
+                    draw_right_aligned_text(
+                        action.label(),
+                        VIRTUAL_WIDTH / 2.0 - 20.0,
+                        y,
+                        44,
+                        LIGHTGRAY,
+                    );
+                    draw_scaled_text(
+                        &key_name(world.settings().controls.key(action)),
+                        VIRTUAL_WIDTH / 2.0 + 20.0,
+                        y,
+                        44,
+                        WHITE,
+                    );
+                    // player two's keys alongside, in their bee's colour
+                    if world.player_count() > 1 && Action::PER_PLAYER.contains(&action) {
+                        draw_scaled_text(
+                            &key_name(world.settings().player_two_controls.key(action)),
+                            VIRTUAL_WIDTH / 2.0 + 280.0,
+                            y,
+                            44,
+                            PLAYER_COLORS[1],
+                        );
+                    }
+                }
+            }
+            MenuPage::HighScores => {
+                draw_centered_text(self.page.title(), VIRTUAL_WIDTH / 2.0, 200.0, 80, WHITE);
+                let (_, mode) = BOARDS[self.menu.item(MenuId::Board).selected()];
+                match world.high_scores(mode) {
+                    Some(high_scores) => high_scores.draw(320.0, None),
+                    None => draw_best_splits(world.best_splits(), 320.0),
+                }
+            }
+            MenuPage::Achievements => world.achievements().draw_gallery(),
+            MenuPage::Controls | MenuPage::PlayerTwoControls => {
+                draw_centered_text(self.page.title(), VIRTUAL_WIDTH / 2.0, 150.0, 80, WHITE);
+            }
+            _ => draw_centered_text(self.page.title(), VIRTUAL_WIDTH / 2.0, 250.0, 80, WHITE),
+        }
+
+        self.menu.draw();
+
+        if let Some(action) = self.rebinding {
+            draw_centered_text(
+                &format!("Press a key for {} (Backspace cancels)", action.label()),
+                VIRTUAL_WIDTH / 2.0,
+                VIRTUAL_HEIGHT - 40.0,
+                40,
+                GOLD,
+            );
+        }
+    }
+
+    fn is_overlay(&self) -> bool {
+        self.page == MenuPage::Pause
+    }
+
+    /// Pages opened from the pause menu keep the stage music going.
+    fn music(&self) -> Option<MusicTrack> {
+        if self.page == MenuPage::Main {
+            Some(MusicTrack::Title)
+        } else {
+            None
+        }
+    }
+}
+
+/// Between stages: the stage's bonuses and, in a campaign, founding hives with
+/// the honey saved up.
+#[derive(Debug)]
+pub struct ShopScene;
+
+impl Scene for ShopScene {
+    fn update(&mut self, world: &mut World) -> Transition {
+        let controls = world.settings().controls;
+        if world.mode() == GameMode::Campaign && controls.is_pressed(Action::BuildHive) {
+            world.found_hive();
+        }
+
+        if is_key_pressed(KeyCode::Enter) {
+            world.next_stage();
+            Transition::Pop
+        } else {
+            Transition::None
+        }
+    }
+
+    fn draw(&self, world: &World) {
+        dim_background();
+
+        draw_centered_text(
+            &format!("You saved {} hives!", world.hives_left()),
+            VIRTUAL_WIDTH / 2.0,
+            VIRTUAL_HEIGHT / 2.0,
+            100,
+            WHITE,
+        );
+
+        draw_centered_text(
+            "Press ENTER to progress",
+            VIRTUAL_WIDTH / 2.0,
+            VIRTUAL_HEIGHT / 2.0 + 100.0,
+            50,
+            WHITE,
+        );
+
+        if world.mode() == GameMode::Campaign {
+            draw_centered_text(
+                &format!(
+                    "Press {} to found a new hive ({} honey)",
+                    key_name(world.settings().controls.key(Action::BuildHive)),
+                    HIVE_COST
+                ),
+                VIRTUAL_WIDTH / 2.0,
+                VIRTUAL_HEIGHT / 2.0 + 160.0,
+                50,
+                if world.honey() >= HIVE_COST {
+                    WHITE
+                } else {
+                    GRAY
+                },
+            );
+        }
+
+        let mut y = VIRTUAL_HEIGHT / 2.0 - 200.0;
+        for (name, bonus) in &world.score().stage_bonuses {
+            draw_centered_text(
+                &format!("{} +{}", name, bonus),
+                VIRTUAL_WIDTH / 2.0,
+                y,
+                50,
+                GOLD,
+            );
+            y += 60.0;
+        }
+    }
+
+    fn is_overlay(&self) -> bool {
+        true
+    }
+
+    fn music(&self) -> Option<MusicTrack> {
+        Some(MusicTrack::Victory)
+    }
+}
+
+/// The fastest time attack run with when it was set, or a note that there
+/// isn't one yet.
+fn draw_best_splits(best: &BestSplits, y: f32) {
+    match best.total() {
+        Some(total) => {
+            draw_centered_text(
+                &format!("Best time {} on {}", format_time(total), best.date),
+                VIRTUAL_WIDTH / 2.0,
+                y,
+                44,
+                GOLD,
+            );
+            draw_splits(&best.splits, &[], y + 60.0);
+        }
+        None => draw_centered_text(
+            "No time attack run finished yet",
+            VIRTUAL_WIDTH / 2.0,
+            y,
+            44,
+            LIGHTGRAY,
+        ),
+    }
+}
+
+/// The end of a run, lost or finished: initials for a run that made the
+/// high-score table, then the run summary or the table. Time attack runs show
+/// their splits instead of a table.
+#[derive(Debug)]
+pub struct SummaryScene {
+    /// Whether a time or score attack run reached its end rather than being
+    /// lost.
+    finished: bool,
+    /// Where the run placed in the high-score table.
+    placement: Option<usize>,
+    /// A run that made the table, waiting on the player's initials.
+    new_record: Option<HighScore>,
+    /// Whether the high scores or splits are showing rather than the run
+    /// summary.
+    show_table: bool,
+    /// The time attack best as it stood before this run, to compare with.
+    best_splits: Vec<f32>,
+    /// Whether this run set the time attack best.
+    new_best: bool,
+}
+
+impl SummaryScene {
+    pub fn new(world: &mut World, finished: bool) -> Self {
+        let record = world.run_record();
+        let placement = world
+            .high_scores(world.mode())
+            .and_then(|high_scores| high_scores.placement(record.score));
+        let best_splits = world.best_splits().splits.clone();
+        let new_best = finished && world.mode() == GameMode::TimeAttack && world.record_splits();
+
+        // drop whatever was typed while playing
+        while get_char_pressed().is_some() {}
+
+        SummaryScene {
+            finished,
+            placement,
+            new_record: placement.map(|_| record),
+            show_table: world.mode() == GameMode::TimeAttack,
+            best_splits,
+            new_best,
+        }
+    }
+
+    fn enter_initials(&mut self, world: &mut World) {
+        let record = match &mut self.new_record {
+            Some(record) => record,
+            None => return,
+        };
+
+        while let Some(c) = get_char_pressed() {
+            if c.is_ascii_alphabetic() && record.initials.len() < INITIALS_LEN {
+                record.initials.push(c.to_ascii_uppercase());
+            }
+        }
+        if is_key_pressed(KeyCode::Backspace) {
+            record.initials.pop();
+        }
+
+        if is_key_pressed(KeyCode::Enter) && !record.initials.is_empty() {
+            let record = self.new_record.take().unwrap();
+            self.placement = world.record_high_score(record);
+        }
+    }
+}
+
+impl Scene for SummaryScene {
+    fn update(&mut self, world: &mut World) -> Transition {
+        if self.new_record.is_some() {
+            self.enter_initials(world);
+        } else if is_key_pressed(KeyCode::Tab) {
+            self.show_table = !self.show_table;
+        } else if is_key_pressed(KeyCode::Enter) && world.mode() != GameMode::Daily {
+            world.reset();
+            return Transition::Pop;
+        } else if is_key_pressed(KeyCode::Escape) {
+            return Transition::Reset(Box::new(MenuScene::new(MenuPage::Main, world)));
+        }
+
+        Transition::None
+    }
+
+    fn draw(&self, world: &World) {
+        dim_background();
+        let time_attack = world.mode() == GameMode::TimeAttack;
+        let (title, color) = match (self.finished, time_attack) {
+            (false, _) => ("GAME OVER", RED),
+            (true, true) => ("FINISHED", GOLD),
+            (true, false) => ("TIME UP", GOLD),
+        };
+        draw_centered_text(title, VIRTUAL_WIDTH / 2.0, 240.0, 100, color);
+
+        let placement = match (time_attack, self.placement) {
+            (true, _) => match world.splits().last() {
+                Some(&total) if self.finished && self.new_best => {
+                    format!("New best time: {}", format_time(total))
+                }
+                Some(&total) if self.finished => format!("Your time: {}", format_time(total)),
+                _ => format!(
+                    "Cleared {} of {} stages",
+                    world.splits().len(),
+                    TIME_ATTACK_STAGES
+                ),
+            },
+            (false, Some(rank)) => format!("Your run placed #{}", rank + 1),
+            (false, None) => format!("Your run didn't make the top {}", MAX_ENTRIES),
+        };
+        draw_centered_text(&placement, VIRTUAL_WIDTH / 2.0, 330.0, 50, WHITE);
+
+        if let Some(record) = &self.new_record {
+            draw_centered_text("New high score!", VIRTUAL_WIDTH / 2.0, 480.0, 80, GOLD);
+            // pad with underscores for the letters still to come
+            let initials = format!("{:_<width$}", record.initials, width = INITIALS_LEN);
+            draw_centered_text(
+                &format!("Enter your initials: {}", initials),
+                VIRTUAL_WIDTH / 2.0,
+                580.0,
+                60,
+                WHITE,
+            );
+            draw_centered_text(
+                "Press ENTER to confirm",
+                VIRTUAL_WIDTH / 2.0,
+                660.0,
+                50,
+                LIGHTGRAY,
+            );
+        } else {
+            if self.show_table {
+                match world.high_scores(world.mode()) {
+                    Some(high_scores) => high_scores.draw(430.0, self.placement),
+                    None => draw_splits(world.splits(), &self.best_splits, 430.0),
+                }
+            } else {
+                world.stats().draw_summary(450.0, world.score().best_combo);
+            }
+            let retry = if world.mode() == GameMode::Daily {
+                "That was today's attempt"
+            } else {
+                "Press ENTER to try again"
+            };
+            let tab = match (self.show_table, time_attack) {
+                (true, _) => "TAB for the run summary",
+                (false, true) => "TAB for splits",
+                (false, false) => "TAB for high scores",
+            };
+            draw_centered_text(
+                &format!("{}, {}", retry, tab),
+                VIRTUAL_WIDTH / 2.0,
+                VIRTUAL_HEIGHT - 200.0,
+                50,
+                WHITE,
+            );
+            draw_centered_text(
+                "Press ESCAPE for the main menu",
+                VIRTUAL_WIDTH / 2.0,
+                VIRTUAL_HEIGHT - 130.0,
+                40,
+                LIGHTGRAY,
+            );
+        }
+    }
+
+    fn is_overlay(&self) -> bool {
+        true
+    }
+
+    fn music(&self) -> Option<MusicTrack> {
+        Some(MusicTrack::Defeat)
+    }
+}
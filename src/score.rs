@@ -0,0 +1,146 @@
+use super::*;
+
+/// Kills this close together keep a combo going.
+const COMBO_WINDOW_SECS: f32 = 2.0;
+/// Every this many kills in a combo raises the multiplier by one.
+const KILLS_PER_MULTIPLIER: u32 = 5;
+const MAX_MULTIPLIER: u32 = 8;
+/// Per hive still at full HP when a stage is cleared.
+const FULL_HIVE_BONUS: u64 = 500;
+/// For clearing a stage without the bee or any hive taking damage.
+const NO_DAMAGE_BONUS: u64 = 2000;
+const POPUP_SECS: f32 = 1.0;
+/// Units per second.
+const POPUP_RISE_SPEED: f32 = 80.0;
+
+#[derive(Debug)]
+struct Popup {
+    text: String,
+    pos: Vec2,
+    age: f32,
+}
+
+#[derive(Debug, Default)]
+pub struct Score {
+    pub total: u64,
+    pub combo: u32,
+    pub best_combo: u32,
+    combo_timer: f32,
+    /// Whether anything took damage since the stage started.
+    damaged: bool,
+    /// Awarded for the last stage cleared, to show between stages.
+    pub stage_bonuses: Vec<(&'static str, u64)>,
+    popups: Vec<Popup>,
+}
+
+impl Score {
+    pub fn multiplier(&self) -> u32 {
+        (1 + self.combo / KILLS_PER_MULTIPLIER).min(MAX_MULTIPLIER)
+    }
+
+    pub fn handle(&mut self, event: &GameEvent) {
+        match *event {
+            GameEvent::StageStarted { .. } => {
+                self.damaged = false;
+                self.combo = 0;
+                self.stage_bonuses.clear();
+                self.popups.clear();
+            }
+            GameEvent::EnemyKilled { pos, kind } => {
+                self.combo += 1;
+                self.best_combo = self.best_combo.max(self.combo);
+                self.combo_timer = COMBO_WINDOW_SECS;
+
+                let multiplier = self.multiplier();
+                let points = kind.points() * multiplier as u64;
+                self.total += points;
+                self.popup(
+                    if multiplier > 1 {
+                        format!("+{} x{}", points, multiplier)
+                    } else {
+                        format!("+{}", points)
+                    },
+                    pos,
+                );
+            }
+            GameEvent::HiveDamaged { .. } => self.damaged = true,
+            GameEvent::PlayerDamaged { .. } => {
+                self.damaged = true;
+                self.combo = 0;
+            }
+            GameEvent::StageCleared { full_hives, .. } => {
+                if full_hives > 0 {
+                    self.stage_bonuses
+                        .push(("Full hives", FULL_HIVE_BONUS * full_hives as u64));
+                }
+                if !self.damaged {
+                    self.stage_bonuses.push(("No damage", NO_DAMAGE_BONUS));
+                }
+                self.total += self
+                    .stage_bonuses
+                    .iter()
+                    .map(|(_, bonus)| bonus)
+                    .sum::<u64>();
+            }
+            _ => {}
+        }
+    }
+
+    /// Advanced by simulation time, so combos don't run out while paused.
+    pub fn tick(&mut self, dt: f32) {
+        self.combo_timer -= dt;
+        if self.combo_timer <= 0.0 {
+            self.combo = 0;
+        }
+
+        for popup in &mut self.popups {
+            popup.age += dt;
+            popup.pos.y -= POPUP_RISE_SPEED * dt;
+        }
+        self.popups.retain(|popup| popup.age < POPUP_SECS);
+    }
+
+    pub fn reset(&mut self) {
+        *self = Score::default();
+    }
+
+    fn popup(&mut self, text: String, pos: Vec2) {
+        self.popups.push(Popup {
+            text,
+            pos,
+            age: 0.0,
+        });
+    }
+
+    pub fn draw_hud(&self) {
+        draw_h_centered_text(
+            &format!("Score: {}", self.total),
+            VIRTUAL_WIDTH / 2.0,
+            100.0,
+            40,
+            WHITE,
+        );
+
+        if self.multiplier() > 1 {
+            // swell a little on each kill
+            let pulse = 1.0 + 0.3 * (self.combo_timer - COMBO_WINDOW_SECS + 0.2).max(0.0) / 0.2;
+            draw_h_centered_text(
+                &format!("Combo x{}", self.multiplier()),
+                VIRTUAL_WIDTH / 2.0,
+                145.0,
+                (36.0 * pulse) as u16,
+                GOLD,
+            );
+        }
+    }
+}
+
+impl Drawable for Score {
+    /// Popups, in world space.
+    fn draw(&self) {
+        for popup in &self.popups {
+            let color = Color::new(1.0, 1.0, 1.0, 1.0 - popup.age / POPUP_SECS);
+            draw_centered_text(&popup.text, popup.pos.x, popup.pos.y, 36, color);
+        }
+    }
+}
@@ -0,0 +1,331 @@
+use super::*;
+
+use std::fmt::Write;
+
+const SETTINGS_KEY: &str = "settings.cfg";
+/// Prefixes player two's key bindings in the settings file.
+const PLAYER_TWO_PREFIX: &str = "p2_";
+
+/// Window sizes offered in the settings, all in the virtual aspect ratio.
+pub const WINDOW_SIZES: [(i32, i32); 4] = [(800, 600), (1024, 768), (1280, 960), (1600, 1200)];
+
+/// Player preferences, persisted between runs.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Settings {
+    pub fullscreen: bool,
+    /// Only read at startup.
+    pub vsync: bool,
+    pub window_size: (i32, i32),
+    pub show_fps: bool,
+    pub show_minimap: bool,
+    pub mixer: Mixer,
+    pub controls: Controls,
+    /// Only the actions each bee has its own key for are used.
+    pub player_two_controls: Controls,
+    /// How many bees the next run starts with.
+    pub players: usize,
+    pub difficulty: Difficulty,
+    /// Used when the difficulty is `Custom`.
+    pub custom_difficulty: Tuning,
+    /// Ease off or push harder within a stage depending on how it's going.
+    pub adaptive_difficulty: bool,
+    pub effects: EffectsLevel,
+    /// Keep shooting while a shoot key is held, rather than once per press.
+    pub hold_to_fire: bool,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            fullscreen: false,
+            vsync: true,
+            window_size: (VIRTUAL_WIDTH as i32, VIRTUAL_HEIGHT as i32),
+            show_fps: false,
+            show_minimap: true,
+            mixer: Default::default(),
+            controls: Default::default(),
+            player_two_controls: Controls::defaults_for(1),
+            players: 1,
+            difficulty: Default::default(),
+            custom_difficulty: Default::default(),
+            adaptive_difficulty: false,
+            effects: Default::default(),
+            hold_to_fire: false,
+        }
+    }
+}
+
+impl Settings {
+    pub fn tuning(&self) -> Tuning {
+        self.difficulty.preset().unwrap_or(self.custom_difficulty)
+    }
+
+    /// The keys of the bee at index `player`.
+    pub fn player_controls(&self, player: usize) -> Controls {
+        if player == 0 {
+            self.controls
+        } else {
+            self.player_two_controls
+        }
+    }
+
+    pub fn player_controls_mut(&mut self, player: usize) -> &mut Controls {
+        if player == 0 {
+            &mut self.controls
+        } else {
+            &mut self.player_two_controls
+        }
+    }
+
+    /// Binds `key` to one of the bee at `player`'s actions, swapping keys
+    /// with whichever of its other actions had it. Binds nothing and returns
+    /// false if the other bee uses `key`, or if it would leave Escape, which
+    /// also backs out of menus, on anything but Pause.
+    pub fn bind(&mut self, player: usize, action: Action, key: KeyCode) -> bool {
+        let actions = Action::for_player(player);
+        let mut controls = self.player_controls(player);
+        let old = controls.key(action);
+        if let Some(&other) = actions
+            .iter()
+            .find(|&&other| other != action && controls.key(other) == key)
+        {
+            controls.bind(other, old);
+        }
+        controls.bind(action, key);
+
+        let other_bee = self.player_controls(1 - player);
+        let taken = Action::for_player(1 - player)
+            .iter()
+            .any(|&other| other_bee.key(other) == key);
+        let escape_moved = actions
+            .iter()
+            .any(|&other| other != Action::Pause && controls.key(other) == KeyCode::Escape);
+        if taken || escape_moved {
+            return false;
+        }
+        *self.player_controls_mut(player) = controls;
+        true
+    }
+
+    /// Puts the keys of the bee at `player` back to their defaults, and the
+    /// other bee's too if it had taken any of them.
+    pub fn reset_controls(&mut self, player: usize) {
+        *self.player_controls_mut(player) = Controls::defaults_for(player);
+        if self.controls_clash() {
+            *self.player_controls_mut(1 - player) = Controls::defaults_for(1 - player);
+        }
+    }
+
+    /// Whether the bees share a key.
+    fn controls_clash(&self) -> bool {
+        Action::ALL.iter().any(|&one| {
+            Action::PER_PLAYER
+                .iter()
+                .any(|&two| self.controls.key(one) == self.player_two_controls.key(two))
+        })
+    }
+
+    pub fn load() -> Self {
+        match storage::load(SETTINGS_KEY) {
+            Some(text) => Settings::parse(&text),
+            None => Settings::default(),
+        }
+    }
+
+    /// Reads what `serialize` writes; anything missing or unreadable keeps its
+    /// default.
+    fn parse(text: &str) -> Self {
+        let mut settings = Settings::default();
+        for (key, value) in storage::parse_entries(text) {
+            let volume = || value.parse::<f32>().ok().map(|v| v.clamp(0.0, 1.0));
+            let flag = |default: bool| value.parse().unwrap_or(default);
+            let custom = &mut settings.custom_difficulty;
+            match key {
+                "fullscreen" => settings.fullscreen = flag(settings.fullscreen),
+                "vsync" => settings.vsync = flag(settings.vsync),
+                "window_size" => {
+                    let mut parts = value.splitn(2, 'x').map(|n| n.trim().parse::<i32>());
+                    if let (Some(Ok(w)), Some(Ok(h))) = (parts.next(), parts.next()) {
+                        if w > 0 && h > 0 {
+                            settings.window_size = (w, h);
+                        }
+                    }
+                }
+                "show_fps" => settings.show_fps = flag(settings.show_fps),
+                "show_minimap" => settings.show_minimap = flag(settings.show_minimap),
+                "master_volume" => {
+                    settings.mixer.master = volume().unwrap_or(settings.mixer.master)
+                }
+                "music_volume" => settings.mixer.music = volume().unwrap_or(settings.mixer.music),
+                "sfx_volume" => settings.mixer.sfx = volume().unwrap_or(settings.mixer.sfx),
+                "difficulty" => {
+                    settings.difficulty =
+                        Difficulty::from_name(value).unwrap_or(settings.difficulty)
+                }
+                "custom_speed" => custom.speed = value.parse().unwrap_or(custom.speed),
+                "custom_count" => custom.count = value.parse().unwrap_or(custom.count),
+                "custom_player_hp" => custom.player_hp = value.parse().unwrap_or(custom.player_hp),
+                "custom_hive_hp" => custom.hive_hp = value.parse().unwrap_or(custom.hive_hp),
+                "custom_damage" => custom.damage = value.parse().unwrap_or(custom.damage),
+                "adaptive_difficulty" => {
+                    settings.adaptive_difficulty = flag(settings.adaptive_difficulty)
+                }
+                "players" => {
+                    let players = value.parse().unwrap_or(settings.players);
+                    settings.players = players.clamp(1, MAX_PLAYERS);
+                }
+                "effects" => {
+                    settings.effects = EffectsLevel::from_name(value).unwrap_or(settings.effects)
+                }
+                "hold_to_fire" => settings.hold_to_fire = flag(settings.hold_to_fire),
+                _ => {
+                    // anything else should be a key binding, player two's
+                    // marked with a prefix
+                    let (controls, actions, name) = match key.strip_prefix(PLAYER_TWO_PREFIX) {
+                        Some(name) => (
+                            &mut settings.player_two_controls,
+                            &Action::PER_PLAYER[..],
+                            name,
+                        ),
+                        None => (&mut settings.controls, &Action::ALL[..], key),
+                    };
+                    let action = actions.iter().find(|a| a.name() == name);
+                    if let (Some(&action), Some(key)) = (action, key_from_name(value)) {
+                        controls.bind(action, key);
+                    }
+                }
+            }
+        }
+
+        settings.custom_difficulty = settings.custom_difficulty.clamped();
+        // a hand-edited file can give both bees a key that `bind` would refuse
+        if settings.controls_clash() {
+            settings.controls = Controls::defaults_for(0);
+            settings.player_two_controls = Controls::defaults_for(1);
+        }
+        settings
+    }
+
+    pub fn save(&self) {
+        storage::save(SETTINGS_KEY, &self.serialize());
+    }
+
+    fn serialize(&self) -> String {
+        let mut text = format!(
+            "# video\n\
+             fullscreen = {}\n\
+             vsync = {}\n\
+             window_size = {}x{}\n\
+             show_fps = {}\n\
+             show_minimap = {}\n\
+             \n\
+             # audio\n\
+             master_volume = {}\n\
+             music_volume = {}\n\
+             sfx_volume = {}\n\
+             \n\
+             # gameplay\n\
+             difficulty = {}\n\
+             custom_speed = {}\n\
+             custom_count = {}\n\
+             custom_player_hp = {}\n\
+             custom_hive_hp = {}\n\
+             custom_damage = {}\n\
+             adaptive_difficulty = {}\n\
+             players = {}\n\
+             \n\
+             # accessibility\n\
+             effects = {}\n\
+             hold_to_fire = {}\n\
+             \n\
+             # controls\n",
+            self.fullscreen,
+            self.vsync,
+            self.window_size.0,
+            self.window_size.1,
+            self.show_fps,
+            self.show_minimap,
+            self.mixer.master,
+            self.mixer.music,
+            self.mixer.sfx,
+            self.difficulty.name(),
+            self.custom_difficulty.speed,
+            self.custom_difficulty.count,
+            self.custom_difficulty.player_hp,
+            self.custom_difficulty.hive_hp,
+            self.custom_difficulty.damage,
+            self.adaptive_difficulty,
+            self.players,
+            self.effects.name(),
+            self.hold_to_fire,
+        );
+        for action in Action::ALL.iter().copied() {
+            // writing to a String can't fail
+            let _ = writeln!(
+                text,
+                "{} = {}",
+                action.name(),
+                key_name(self.controls.key(action))
+            );
+        }
+        for action in Action::PER_PLAYER.iter().copied() {
+            let _ = writeln!(
+                text,
+                "{}{} = {}",
+                PLAYER_TWO_PREFIX,
+                action.name(),
+                key_name(self.player_two_controls.key(action))
+            );
+        }
+        text
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn defaults_survive_a_round_trip() {
+        let settings = Settings::default();
+        assert_eq!(Settings::parse(&settings.serialize()), settings);
+    }
+
+    #[test]
+    fn changed_settings_and_bindings_survive_a_round_trip() {
+        let mut settings = Settings {
+            fullscreen: true,
+            vsync: false,
+            window_size: WINDOW_SIZES[2],
+            show_fps: true,
+            show_minimap: false,
+            players: 2,
+            difficulty: Difficulty::Custom,
+            adaptive_difficulty: true,
+            hold_to_fire: true,
+            ..Settings::default()
+        };
+        settings.mixer.music = 0.3;
+        assert!(settings.bind(0, Action::Minimap, KeyCode::F));
+        assert!(settings.bind(0, Action::Pause, KeyCode::P));
+        assert!(settings.bind(1, Action::ShootUp, KeyCode::G));
+        assert_eq!(Settings::parse(&settings.serialize()), settings);
+    }
+
+    #[test]
+    fn bees_sharing_a_key_load_the_default_controls() {
+        let settings = Settings::parse("pause = P\np2_move_up = W\n");
+        assert_eq!(settings.controls, Controls::defaults_for(0));
+        assert_eq!(settings.player_two_controls, Controls::defaults_for(1));
+    }
+
+    #[test]
+    fn resetting_a_bee_frees_its_keys_from_the_other() {
+        let mut settings = Settings::default();
+        assert!(settings.bind(0, Action::MoveUp, KeyCode::T));
+        assert!(settings.bind(1, Action::MoveUp, KeyCode::W));
+        settings.reset_controls(0);
+        assert_eq!(settings.controls, Controls::defaults_for(0));
+        assert_eq!(settings.player_two_controls, Controls::defaults_for(1));
+    }
+}
@@ -0,0 +1,212 @@
+use super::*;
+
+use macroquad::{
+    experimental::collections::storage,
+    miniquad::{BlendFactor, BlendState, BlendValue, Equation, PipelineParams},
+};
+
+use std::collections::HashMap;
+
+const ATLAS_TEXTURE_PATH: &str = "assets/atlas.png";
+const ATLAS_DEFINITIONS_PATH: &str = "assets/atlas.txt";
+
+pub const BEE: &str = "bee";
+pub const HORNET: &str = "hornet";
+pub const HIVE: &str = "hive";
+pub const FLOWER: &str = "flower";
+pub const STINGER: &str = "stinger";
+
+/// How strongly a sprite is washed white while flashing from a hit.
+pub const FLASH_ALPHA: f32 = 0.8;
+
+const FLASH_VERTEX_SHADER: &str = r#"#version 100
+attribute vec3 position;
+attribute vec2 texcoord;
+attribute vec4 color0;
+
+varying lowp vec2 uv;
+varying lowp vec4 color;
+
+uniform mat4 Model;
+uniform mat4 Projection;
+
+void main() {
+    gl_Position = Projection * Model * vec4(position, 1);
+    color = color0 / 255.0;
+    uv = texcoord;
+}"#;
+
+/// The sprite's shape in flat white, faded by the draw colour's alpha.
+const FLASH_FRAGMENT_SHADER: &str = r#"#version 100
+varying lowp vec4 color;
+varying lowp vec2 uv;
+
+uniform sampler2D Texture;
+
+void main() {
+    gl_FragColor = vec4(1.0, 1.0, 1.0, texture2D(Texture, uv).a * color.a);
+}"#;
+
+/// A single texture holding every sprite, described by a definitions file with
+/// one `name x y width height` line per sprite. Sprites are drawn facing up
+/// and rotated to face the entity's direction. Animation frames are named
+/// `sprite_clip_frame`, e.g. `bee_flap_2`.
+#[derive(Debug)]
+pub struct Atlas {
+    texture: Texture2D,
+    sprites: HashMap<String, Rect>,
+    /// Draws sprites as white silhouettes for hit flashes, if the shader
+    /// compiled.
+    flash: Option<Material>,
+}
+
+impl Atlas {
+    /// Loads the atlas and stores it for `draw_sprite`. Without one, everything
+    /// keeps using its procedural drawing.
+    pub async fn load() {
+        let texture = match load_texture(ATLAS_TEXTURE_PATH).await {
+            Ok(texture) => texture,
+            Err(_) => return,
+        };
+        let definitions = match load_string(ATLAS_DEFINITIONS_PATH).await {
+            Ok(definitions) => definitions,
+            Err(_) => return,
+        };
+
+        texture.set_filter(FilterMode::Nearest);
+        storage::store(Atlas {
+            texture,
+            sprites: parse_definitions(&definitions),
+            flash: load_flash_material(),
+        });
+    }
+
+    /// Washes an already drawn sprite white, `alpha` strong.
+    fn draw_flash(&self, name: &str, center: Vec2, size: Vec2, rotation: f32, alpha: f32) {
+        let material = match self.flash {
+            Some(material) => material,
+            None => return,
+        };
+
+        gl_use_material(material);
+        self.draw(
+            name,
+            center,
+            size,
+            rotation,
+            Color::new(1.0, 1.0, 1.0, alpha),
+        );
+        gl_use_default_material();
+    }
+
+    fn draw(&self, name: &str, center: Vec2, size: Vec2, rotation: f32, color: Color) -> bool {
+        let source = match self.sprites.get(name) {
+            Some(source) => *source,
+            None => return false,
+        };
+
+        draw_texture_ex(
+            self.texture,
+            center.x - size.x / 2.0,
+            center.y - size.y / 2.0,
+            color,
+            DrawTextureParams {
+                dest_size: Some(size),
+                source: Some(source),
+                rotation,
+                ..Default::default()
+            },
+        );
+
+        true
+    }
+}
+
+fn load_flash_material() -> Option<Material> {
+    let params = MaterialParams {
+        pipeline_params: PipelineParams {
+            color_blend: Some(BlendState::new(
+                Equation::Add,
+                BlendFactor::Value(BlendValue::SourceAlpha),
+                BlendFactor::OneMinusValue(BlendValue::SourceAlpha),
+            )),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+
+    load_material(FLASH_VERTEX_SHADER, FLASH_FRAGMENT_SHADER, params).ok()
+}
+
+fn parse_definitions(definitions: &str) -> HashMap<String, Rect> {
+    definitions
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter_map(|line| {
+            let mut parts = line.split_whitespace();
+            let name = parts.next()?;
+            let mut next = || parts.next().and_then(|s| s.parse::<f32>().ok());
+
+            Some((
+                name.to_owned(),
+                Rect::new(next()?, next()?, next()?, next()?),
+            ))
+        })
+        .collect()
+}
+
+/// Draws the named sprite, `size` being its size when facing up, centered on
+/// `center`. Returns false when there's no atlas or no such sprite in it, so
+/// the caller can fall back to procedural drawing.
+pub fn draw_sprite(name: &str, center: Vec2, size: Vec2, rotation: f32, color: Color) -> bool {
+    match storage::try_get::<Atlas>() {
+        Some(atlas) => atlas.draw(name, center, size, rotation, color),
+        None => false,
+    }
+}
+
+/// Like `draw_sprite`, but picks the animation's current frame, falling back to
+/// the plain sprite when the atlas doesn't have frames for the clip.
+pub fn draw_animated_sprite(
+    name: &str,
+    animation: &Animation,
+    center: Vec2,
+    size: Vec2,
+    rotation: f32,
+    color: Color,
+) -> bool {
+    let atlas = match storage::try_get::<Atlas>() {
+        Some(atlas) => atlas,
+        None => return false,
+    };
+
+    let frame = animated_frame(&atlas, name, animation);
+    atlas.draw(&frame, center, size, rotation, color)
+}
+
+/// Washes the sprite `draw_animated_sprite` drew white, for a hit flash that
+/// shows whatever the sprite's colours. Does nothing without an atlas.
+pub fn flash_animated_sprite(
+    name: &str,
+    animation: &Animation,
+    center: Vec2,
+    size: Vec2,
+    rotation: f32,
+) {
+    if let Some(atlas) = storage::try_get::<Atlas>() {
+        let frame = animated_frame(&atlas, name, animation);
+        atlas.draw_flash(&frame, center, size, rotation, FLASH_ALPHA);
+    }
+}
+
+/// The animation's current frame, or the plain sprite when the atlas doesn't
+/// have frames for the clip.
+fn animated_frame(atlas: &Atlas, name: &str, animation: &Animation) -> String {
+    let frame = format!("{}_{}_{}", name, animation.clip().name, animation.frame());
+    if atlas.sprites.contains_key(&frame) {
+        frame
+    } else {
+        name.to_owned()
+    }
+}
@@ -0,0 +1,98 @@
+use super::*;
+
+use std::collections::HashMap;
+
+/// Tallies for the whole run, shown once it's over.
+#[derive(Debug, Default, Clone)]
+pub struct RunStats {
+    pub stages_cleared: usize,
+    pub kills: HashMap<EnemyKind, u32>,
+    pub shots_fired: u32,
+    pub shots_hit: u32,
+    /// HP the bees lost.
+    pub damage_taken: u32,
+    /// HP the hives lost.
+    pub hive_damage: u32,
+    /// Seconds spent playing, not counting pauses or the screens between stages.
+    pub time_survived: f32,
+}
+
+impl RunStats {
+    pub fn handle(&mut self, event: &GameEvent) {
+        match *event {
+            GameEvent::Shot { .. } => self.shots_fired += 1,
+            GameEvent::EnemyHit { .. } => self.shots_hit += 1,
+            GameEvent::EnemyKilled { kind, .. } => *self.kills.entry(kind).or_insert(0) += 1,
+            GameEvent::PlayerDamaged { lost, .. } => self.damage_taken += lost as u32,
+            GameEvent::HiveDamaged { lost, .. } => self.hive_damage += lost as u32,
+            GameEvent::StageCleared { .. } => self.stages_cleared += 1,
+            _ => {}
+        }
+    }
+
+    pub fn tick(&mut self, dt: f32) {
+        self.time_survived += dt;
+    }
+
+    pub fn reset(&mut self) {
+        *self = RunStats::default();
+    }
+
+    pub fn kills_of(&self, kind: EnemyKind) -> u32 {
+        self.kills.get(&kind).copied().unwrap_or(0)
+    }
+
+    pub fn total_kills(&self) -> u32 {
+        self.kills.values().sum()
+    }
+
+    /// Fraction of stingers that found a hornet. A stinger stops in the first
+    /// hornet it hits, so every hit is a different stinger.
+    pub fn accuracy(&self) -> f32 {
+        if self.shots_fired == 0 {
+            0.0
+        } else {
+            self.shots_hit as f32 / self.shots_fired as f32
+        }
+    }
+
+    /// Two columns of labelled figures, starting at `y` in HUD space.
+    pub fn draw_summary(&self, y: f32, best_combo: u32) {
+        let secs = self.time_survived as u32;
+        let mut rows = vec![
+            (
+                "Stages cleared".to_string(),
+                self.stages_cleared.to_string(),
+            ),
+            ("Hornets killed".to_string(), self.total_kills().to_string()),
+        ];
+        for kind in EnemyKind::ALL.iter().copied() {
+            rows.push((
+                format!("  {}s", kind.name()),
+                self.kills_of(kind).to_string(),
+            ));
+        }
+        rows.extend(vec![
+            ("Shots fired".to_string(), self.shots_fired.to_string()),
+            (
+                "Accuracy".to_string(),
+                format!("{:.0}%", self.accuracy() * 100.0),
+            ),
+            ("Damage taken".to_string(), self.damage_taken.to_string()),
+            ("Hive damage".to_string(), self.hive_damage.to_string()),
+            (
+                "Time survived".to_string(),
+                format!("{}:{:02}", secs / 60, secs % 60),
+            ),
+            ("Best combo".to_string(), best_combo.to_string()),
+        ]);
+
+        let per_column = rows.len() / 2 + rows.len() % 2;
+        for (i, (label, value)) in rows.iter().enumerate() {
+            let left = if i < per_column { 250.0 } else { 850.0 };
+            let y = y + 55.0 * (i % per_column) as f32;
+            draw_scaled_text(label, left, y, 44, LIGHTGRAY);
+            draw_right_aligned_text(value, left + 500.0, y, 44, WHITE);
+        }
+    }
+}
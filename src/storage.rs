@@ -0,0 +1,70 @@
+//! Small string key-value persistence: files in the user's config directory
+//! on native, and `localStorage` on the web (see the plugin in `web/index.html`).
+
+#[cfg(not(target_arch = "wasm32"))]
+mod native {
+    use std::{env, fs, path::PathBuf};
+
+    fn dir() -> PathBuf {
+        let base = if cfg!(windows) {
+            env::var_os("APPDATA").map(PathBuf::from)
+        } else {
+            env::var_os("XDG_CONFIG_HOME")
+                .map(PathBuf::from)
+                .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))
+        };
+
+        base.unwrap_or_default().join("hornet-invaders")
+    }
+
+    pub fn load(key: &str) -> Option<String> {
+        fs::read_to_string(dir().join(key)).ok()
+    }
+
+    pub fn save(key: &str, value: &str) {
+        let dir = dir();
+        if let Err(e) = fs::create_dir_all(&dir).and_then(|_| fs::write(dir.join(key), value)) {
+            eprintln!("Failed to save {}: {}", key, e);
+        }
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+mod web {
+    extern "C" {
+        fn hornet_storage_len(key: *const u8, key_len: usize) -> i32;
+        fn hornet_storage_get(key: *const u8, key_len: usize, buf: *mut u8, buf_len: usize);
+        fn hornet_storage_set(key: *const u8, key_len: usize, value: *const u8, value_len: usize);
+    }
+
+    pub fn load(key: &str) -> Option<String> {
+        let len = unsafe { hornet_storage_len(key.as_ptr(), key.len()) };
+        if len < 0 {
+            return None;
+        }
+
+        let mut buf = vec![0; len as usize];
+        unsafe { hornet_storage_get(key.as_ptr(), key.len(), buf.as_mut_ptr(), buf.len()) };
+        String::from_utf8(buf).ok()
+    }
+
+    pub fn save(key: &str, value: &str) {
+        unsafe { hornet_storage_set(key.as_ptr(), key.len(), value.as_ptr(), value.len()) };
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub use native::{load, save};
+#[cfg(target_arch = "wasm32")]
+pub use web::{load, save};
+
+/// Parses `key = value` lines, ignoring blank lines and `#` comments.
+pub fn parse_entries(text: &str) -> impl Iterator<Item = (&str, &str)> {
+    text.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter_map(|line| {
+            let mut parts = line.splitn(2, '=');
+            Some((parts.next()?.trim(), parts.next()?.trim()))
+        })
+}
@@ -0,0 +1,326 @@
+//! A tiny sfxr-style synthesizer, so the game has sound without shipping any
+//! audio files.
+
+use super::*;
+
+use std::f32::consts::TAU;
+
+pub const SAMPLE_RATE: u32 = 44100;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Waveform {
+    Square,
+    Sawtooth,
+    Sine,
+    Noise,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SynthParams {
+    pub waveform: Waveform,
+    /// Hz.
+    pub base_freq: f32,
+    /// Hz per second, negative slides down.
+    pub freq_slide: f32,
+    /// Hz, the slide stops here.
+    pub min_freq: f32,
+    /// Fraction of each square wave cycle spent high.
+    pub duty: f32,
+    /// Fraction the pitch wobbles by.
+    pub vibrato_depth: f32,
+    /// Hz.
+    pub vibrato_speed: f32,
+    /// Envelope stage lengths, in seconds.
+    pub attack: f32,
+    pub sustain: f32,
+    pub decay: f32,
+    /// Extra volume at the start of the sustain, fading over it.
+    pub punch: f32,
+    pub volume: f32,
+}
+
+impl Default for SynthParams {
+    fn default() -> Self {
+        SynthParams {
+            waveform: Waveform::Square,
+            base_freq: 440.0,
+            freq_slide: 0.0,
+            min_freq: 20.0,
+            duty: 0.5,
+            vibrato_depth: 0.0,
+            vibrato_speed: 0.0,
+            attack: 0.0,
+            sustain: 0.1,
+            decay: 0.1,
+            punch: 0.0,
+            volume: 0.5,
+        }
+    }
+}
+
+impl SynthParams {
+    pub fn duration(&self) -> f32 {
+        self.attack + self.sustain + self.decay
+    }
+
+    fn envelope(&self, t: f32) -> f32 {
+        if t < self.attack {
+            t / self.attack
+        } else if t < self.attack + self.sustain {
+            1.0 + self.punch * (1.0 - (t - self.attack) / self.sustain)
+        } else {
+            (1.0 - (t - self.attack - self.sustain) / self.decay).max(0.0)
+        }
+    }
+}
+
+/// Renders mono samples from -1 to 1 at `SAMPLE_RATE`. Deterministic, noise
+/// included, so the same parameters always sound the same.
+pub fn render(params: &SynthParams) -> Vec<f32> {
+    let len = (params.duration() * SAMPLE_RATE as f32) as usize;
+    let mut samples = Vec::with_capacity(len);
+
+    let mut phase = 0.0;
+    let mut rng = 0x2545_f491_u32;
+    let mut noise = 0.0;
+
+    for i in 0..len {
+        let t = i as f32 / SAMPLE_RATE as f32;
+        let vibrato = 1.0 + params.vibrato_depth * (TAU * params.vibrato_speed * t).sin();
+        let freq = (params.base_freq + params.freq_slide * t).max(params.min_freq) * vibrato;
+
+        phase += freq / SAMPLE_RATE as f32;
+        if phase >= 1.0 {
+            phase -= phase.floor();
+
+            // xorshift, resampled once per cycle like sfxr so pitch still matters
+            rng ^= rng << 13;
+            rng ^= rng >> 17;
+            rng ^= rng << 5;
+            noise = rng as f32 / u32::MAX as f32 * 2.0 - 1.0;
+        }
+
+        let sample = match params.waveform {
+            Waveform::Square => {
+                if phase < params.duty {
+                    1.0
+                } else {
+                    -1.0
+                }
+            }
+            Waveform::Sawtooth => 1.0 - 2.0 * phase,
+            Waveform::Sine => (TAU * phase).sin(),
+            Waveform::Noise => noise,
+        };
+
+        samples.push((sample * params.envelope(t) * params.volume).clamp(-1.0, 1.0));
+    }
+
+    samples
+}
+
+/// Encodes samples as a 16-bit mono PCM WAV file.
+pub fn to_wav(samples: &[f32]) -> Vec<u8> {
+    let data_len = samples.len() as u32 * 2;
+    let mut wav = Vec::with_capacity(44 + data_len as usize);
+
+    wav.extend_from_slice(b"RIFF");
+    wav.extend_from_slice(&(36 + data_len).to_le_bytes());
+    wav.extend_from_slice(b"WAVE");
+
+    wav.extend_from_slice(b"fmt ");
+    wav.extend_from_slice(&16u32.to_le_bytes());
+    wav.extend_from_slice(&1u16.to_le_bytes()); // PCM
+    wav.extend_from_slice(&1u16.to_le_bytes()); // mono
+    wav.extend_from_slice(&SAMPLE_RATE.to_le_bytes());
+    wav.extend_from_slice(&(SAMPLE_RATE * 2).to_le_bytes());
+    wav.extend_from_slice(&2u16.to_le_bytes());
+    wav.extend_from_slice(&16u16.to_le_bytes());
+
+    wav.extend_from_slice(b"data");
+    wav.extend_from_slice(&data_len.to_le_bytes());
+    for sample in samples {
+        wav.extend_from_slice(&((sample * i16::MAX as f32) as i16).to_le_bytes());
+    }
+
+    wav
+}
+
+pub fn preset(effect: SoundEffect) -> SynthParams {
+    match effect {
+        SoundEffect::Shoot => SynthParams {
+            waveform: Waveform::Square,
+            base_freq: 900.0,
+            freq_slide: -4000.0,
+            min_freq: 200.0,
+            duty: 0.3,
+            sustain: 0.05,
+            decay: 0.1,
+            volume: 0.25,
+            ..Default::default()
+        },
+        SoundEffect::Hit => SynthParams {
+            waveform: Waveform::Noise,
+            base_freq: 2000.0,
+            freq_slide: -8000.0,
+            min_freq: 400.0,
+            sustain: 0.03,
+            decay: 0.08,
+            punch: 0.5,
+            volume: 0.3,
+            ..Default::default()
+        },
+        SoundEffect::Kill => SynthParams {
+            waveform: Waveform::Noise,
+            base_freq: 800.0,
+            freq_slide: -1500.0,
+            min_freq: 100.0,
+            sustain: 0.08,
+            decay: 0.25,
+            punch: 0.8,
+            volume: 0.4,
+            ..Default::default()
+        },
+        SoundEffect::HiveDamage => SynthParams {
+            waveform: Waveform::Sawtooth,
+            base_freq: 220.0,
+            freq_slide: -300.0,
+            min_freq: 60.0,
+            vibrato_depth: 0.2,
+            vibrato_speed: 20.0,
+            sustain: 0.15,
+            decay: 0.2,
+            punch: 0.4,
+            volume: 0.35,
+            ..Default::default()
+        },
+        SoundEffect::PlayerDamage => SynthParams {
+            waveform: Waveform::Square,
+            base_freq: 400.0,
+            freq_slide: -1200.0,
+            min_freq: 80.0,
+            duty: 0.5,
+            sustain: 0.1,
+            decay: 0.2,
+            punch: 0.6,
+            volume: 0.35,
+            ..Default::default()
+        },
+        SoundEffect::StageClear => SynthParams {
+            waveform: Waveform::Sine,
+            base_freq: 520.0,
+            freq_slide: 800.0,
+            vibrato_depth: 0.05,
+            vibrato_speed: 8.0,
+            attack: 0.02,
+            sustain: 0.3,
+            decay: 0.3,
+            volume: 0.3,
+            ..Default::default()
+        },
+        SoundEffect::GameOver => SynthParams {
+            waveform: Waveform::Sawtooth,
+            base_freq: 330.0,
+            freq_slide: -250.0,
+            min_freq: 60.0,
+            vibrato_depth: 0.08,
+            vibrato_speed: 5.0,
+            attack: 0.02,
+            sustain: 0.5,
+            decay: 0.6,
+            volume: 0.35,
+            ..Default::default()
+        },
+    }
+}
+
+/// Hornet buzz, meant to be looped: a whole number of cycles long with a flat
+/// envelope so it doesn't click at the seam.
+pub fn hornet_drone() -> SynthParams {
+    let base_freq = 150.0;
+    let cycles = 60.0;
+
+    SynthParams {
+        waveform: Waveform::Sawtooth,
+        base_freq,
+        sustain: cycles / base_freq,
+        decay: 0.0,
+        volume: 0.3,
+        ..Default::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn presets() -> Vec<SynthParams> {
+        SoundEffect::ALL
+            .iter()
+            .map(|&effect| preset(effect))
+            .collect()
+    }
+
+    #[test]
+    fn renders_duration_times_rate_samples() {
+        for params in presets() {
+            let expected = (params.duration() * SAMPLE_RATE as f32) as usize;
+            assert_eq!(render(&params).len(), expected);
+        }
+    }
+
+    #[test]
+    fn envelope_ends_at_zero() {
+        for params in presets() {
+            // zero, give or take float rounding
+            assert!(params.envelope(params.duration()).abs() < 1e-6);
+
+            let samples = render(&params);
+            let last = samples.last().unwrap();
+            assert!(last.abs() < 0.01, "last sample {} for {:?}", last, params);
+        }
+    }
+
+    #[test]
+    fn samples_stay_in_range() {
+        let loud = SynthParams {
+            punch: 2.0,
+            volume: 1.0,
+            ..Default::default()
+        };
+        for params in presets().into_iter().chain(Some(loud)) {
+            assert!(render(&params).iter().all(|s| (-1.0..=1.0).contains(s)));
+        }
+    }
+
+    #[test]
+    fn same_preset_renders_the_same() {
+        for effect in SoundEffect::ALL.iter().copied() {
+            assert_eq!(render(&preset(effect)), render(&preset(effect)));
+        }
+    }
+
+    #[test]
+    fn wav_header_describes_16_bit_mono_pcm() {
+        let samples = render(&preset(SoundEffect::Shoot));
+        let wav = to_wav(&samples);
+        let u16_at = |i: usize| u16::from_le_bytes([wav[i], wav[i + 1]]);
+        let u32_at = |i: usize| u32::from_le_bytes([wav[i], wav[i + 1], wav[i + 2], wav[i + 3]]);
+        let data_len = samples.len() as u32 * 2;
+
+        assert_eq!(&wav[0..4], b"RIFF");
+        assert_eq!(u32_at(4), 36 + data_len);
+        assert_eq!(&wav[8..12], b"WAVE");
+        assert_eq!(&wav[12..16], b"fmt ");
+        assert_eq!(u32_at(16), 16);
+        assert_eq!(u16_at(20), 1, "PCM");
+        assert_eq!(u16_at(22), 1, "mono");
+        assert_eq!(u32_at(24), SAMPLE_RATE);
+        assert_eq!(u32_at(28), SAMPLE_RATE * 2, "byte rate");
+        assert_eq!(u16_at(32), 2, "block align");
+        assert_eq!(u16_at(34), 16, "bits per sample");
+        assert_eq!(&wav[36..40], b"data");
+        assert_eq!(u32_at(40), data_len);
+        assert_eq!(wav.len(), 44 + data_len as usize);
+    }
+}
@@ -2,6 +2,9 @@ use super::*;
 
 use std::f32::consts::FRAC_PI_3;
 
+/// Sideways sway of the top of a hive, per frame of its damage wobble.
+const WOBBLE_OFFSETS: [f32; 6] = [0.0, -8.0, 6.0, -4.0, 2.0, 0.0];
+
 #[derive(Debug)]
 pub struct Terrain {
     pub x: f32,
@@ -22,36 +25,39 @@ impl Positioned for Terrain {
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum TerrainKind {
-    Block,
     Flower,
 }
 
-impl Default for Terrain {
-    fn default() -> Self {
-        let width = rand::gen_range(50.0, 300.0);
-        let height = rand::gen_range(50.0, 300.0);
+impl Terrain {
+    /// Randomly sized and placed somewhere within `bounds`.
+    pub fn random(kind: TerrainKind, bounds: Rect, rng: &mut Rng) -> Self {
+        let width = rng.gen_range(50.0, 300.0);
+        let height = rng.gen_range(50.0, 300.0);
 
         Terrain {
-            x: rand::gen_range(0.0, screen_width() - width),
-            y: rand::gen_range(0.0, screen_height() - height),
+            x: rng.gen_range(bounds.left(), bounds.right() - width),
+            y: rng.gen_range(bounds.top(), bounds.bottom() - height),
             width,
             height,
-            kind: Default::default(),
+            kind,
         }
     }
 }
 
-impl Default for TerrainKind {
-    fn default() -> Self {
-        TerrainKind::Block
-    }
-}
-
 impl Drawable for Terrain {
     fn draw(&self) {
         match self.kind {
-            TerrainKind::Block => draw_rectangle(self.x, self.y, self.width, self.height, MAGENTA),
             TerrainKind::Flower => {
+                if draw_sprite(
+                    FLOWER,
+                    self.bounding_box().center(),
+                    Vec2::new(self.width, self.height),
+                    0.0,
+                    WHITE,
+                ) {
+                    return;
+                }
+
                 let half_width = self.width / 2.0;
                 let mid_x = self.x + half_width;
                 let mid_y = self.y + half_width;
@@ -92,7 +98,7 @@ impl Actor for Terrain {
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Hive {
     pub x: f32,
     pub y: f32,
@@ -100,33 +106,71 @@ pub struct Hive {
     pub height: f32,
     pub max_hp: i32,
     pub hp: i32,
+    /// Seconds left to flash white after being hit.
+    pub flash: f32,
+    pub wobble: Animation,
 }
 
-impl Default for Hive {
-    fn default() -> Self {
+impl Hive {
+    pub fn damage(&mut self, amount: i32) {
+        self.hp -= amount;
+        self.wobble.restart(DAMAGE_WOBBLE);
+    }
+
+    /// Placed somewhere within `bounds`.
+    pub fn random(bounds: Rect, hp: i32, rng: &mut Rng) -> Self {
         let width = 100.0;
         let height = 100.0;
-        let hp = 5;
 
         Hive {
-            x: rand::gen_range(0.0, screen_width() - width),
-            y: rand::gen_range(0.0, screen_height() - height),
+            x: rng.gen_range(bounds.left(), bounds.right() - width),
+            y: rng.gen_range(bounds.top(), bounds.bottom() - height),
             width,
             height,
             max_hp: hp,
             hp,
+            flash: 0.0,
+            wobble: Default::default(),
         }
     }
 }
 
-impl Drawable for Hive {
-    fn draw(&self) {
-        let color = Color::new(
+impl Hive {
+    /// Fades from yellow to red as the hive is damaged.
+    pub fn color(&self) -> Color {
+        Color::new(
             0.9,
             0.9 * ((self.hp - 1) as f32 / self.max_hp as f32),
             0.0,
             1.0,
-        );
+        )
+    }
+}
+
+impl Drawable for Hive {
+    fn draw(&self) {
+        let health = 0.5 + 0.5 * (self.hp as f32 / self.max_hp as f32);
+        let center = self.bounding_box().center();
+        let size = Vec2::new(self.width, self.height);
+        if draw_animated_sprite(
+            HIVE,
+            &self.wobble,
+            center,
+            size,
+            0.0,
+            Color::new(1.0, health, health, 1.0),
+        ) {
+            if self.flash > 0.0 {
+                flash_animated_sprite(HIVE, &self.wobble, center, size, 0.0);
+            }
+            return;
+        }
+
+        let color = if self.flash > 0.0 {
+            WHITE
+        } else {
+            self.color()
+        };
         const NUM_CHUNKS: usize = 5;
 
         let chunk_height = self.height / NUM_CHUNKS as f32;
@@ -135,8 +179,15 @@ impl Drawable for Hive {
         let mut width = self.width;
         let chunk_width_delta = (self.width * 0.75) / NUM_CHUNKS as f32;
 
-        for _ in 0..NUM_CHUNKS {
-            let x = self.x + (self.width - width) / 2.0;
+        let sway = if self.wobble.clip() == DAMAGE_WOBBLE {
+            WOBBLE_OFFSETS[self.wobble.frame()]
+        } else {
+            0.0
+        };
+
+        for i in 0..NUM_CHUNKS {
+            // the higher the chunk, the further it sways
+            let x = self.x + (self.width - width) / 2.0 + sway * i as f32 / NUM_CHUNKS as f32;
             draw_rectangle(x, y, width, chunk_height, color);
             let r = chunk_height / 2.0;
 
@@ -150,6 +201,12 @@ impl Drawable for Hive {
 }
 
 impl Actor for Hive {
+    fn tick(&mut self) {
+        let dt = get_frame_time();
+        self.flash = (self.flash - dt).max(0.0);
+        self.wobble.tick(dt);
+    }
+
     fn bounding_box(&self) -> Rect {
         Rect {
             x: self.x,
@@ -0,0 +1,47 @@
+use super::*;
+
+/// Everything is laid out and simulated in this resolution, then scaled to fit
+/// the window with letterboxing so gameplay is identical at any window size.
+pub const VIRTUAL_WIDTH: f32 = 1600.0;
+pub const VIRTUAL_HEIGHT: f32 = 1200.0;
+
+/// Physical pixels per virtual unit.
+pub fn view_scale() -> f32 {
+    (screen_width() / VIRTUAL_WIDTH).min(screen_height() / VIRTUAL_HEIGHT)
+}
+
+/// The letterboxed area of the window the game is drawn to, in physical pixels.
+pub fn viewport() -> (i32, i32, i32, i32) {
+    let scale = view_scale();
+    let w = VIRTUAL_WIDTH * scale;
+    let h = VIRTUAL_HEIGHT * scale;
+
+    (
+        ((screen_width() - w) / 2.0) as i32,
+        ((screen_height() - h) / 2.0) as i32,
+        w as i32,
+        h as i32,
+    )
+}
+
+/// Maps the given virtual rect onto the letterboxed viewport.
+pub fn view_camera(rect: Rect) -> Camera2D {
+    let mut camera = Camera2D::from_display_rect(rect);
+    camera.viewport = Some(viewport());
+    camera
+}
+
+/// Camera for overlays and the HUD, spanning the whole virtual resolution.
+pub fn hud_camera() -> Camera2D {
+    view_camera(Rect::new(0.0, 0.0, VIRTUAL_WIDTH, VIRTUAL_HEIGHT))
+}
+
+/// The mouse cursor in virtual units. Over the letterbox bars it falls outside
+/// the virtual resolution.
+pub fn virtual_mouse_position() -> Vec2 {
+    let (x, y, _, _) = viewport();
+    let (mouse_x, mouse_y) = mouse_position();
+    let scale = view_scale();
+
+    Vec2::new((mouse_x - x as f32) / scale, (mouse_y - y as f32) / scale)
+}
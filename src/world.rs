@@ -1,278 +1,990 @@
 use super::*;
 
-#[derive(Debug, Default)]
+const STARTING_HIVES: usize = 3;
+pub const HIVE_COST: u32 = 15;
+/// Spots tried for a new hive or flower before giving up on a crowded arena.
+const PLACEMENT_ATTEMPTS: usize = 50;
+const ARENA_WIDTH: f32 = 4800.0;
+const ARENA_HEIGHT: f32 = 3600.0;
+const MINIMAP_WIDTH: f32 = 320.0;
+const MINIMAP_MARGIN: f32 = 20.0;
+/// How far apart the bees start a stage.
+const PLAYER_SPACING: f32 = 100.0;
+/// How close to the edge of the screen one bee can drag another.
+const TETHER_MARGIN: f32 = 60.0;
+/// How many stages a time attack run has to clear.
+pub const TIME_ATTACK_STAGES: usize = 5;
+/// Every time attack run is the same, so times can be compared.
+const TIME_ATTACK_SEED: u64 = 0x4849_5645;
+/// How long a score attack run lasts.
+pub const SCORE_ATTACK_SECS: f32 = 180.0;
+
+/// How things stood as a stage started, for restarting it.
+#[derive(Debug)]
+struct Checkpoint {
+    score: u64,
+    best_combo: u32,
+    honey: u32,
+    hives: Vec<Hive>,
+    stats: RunStats,
+}
+
+/// How a stage came to an end.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StageOutcome {
+    Cleared,
+    Lost,
+    /// A time or score attack run reached its end.
+    Finished,
+}
+
+#[derive(Debug)]
 pub struct World {
-    state: GameState,
-    player: Player,
+    mode: GameMode,
+    bounds: Rect,
+    camera: FollowCamera,
+    /// The bees in play, player one first.
+    players: Vec<Player>,
+    /// What the run's stages are seeded from.
+    seed: u64,
+    /// Scenery and hive placement, reseeded each stage.
+    layout_rng: Rng,
+    /// Which hornets come from where, reseeded each stage.
+    spawn_rng: Rng,
     stage: usize,
     hives_saved: usize,
+    honey: u32,
     projectiles: Vec<Projectile>,
     enemies: Vec<Enemy>,
+    /// Killed hornets playing their death animation.
+    dying: Vec<Enemy>,
     enemies_remaining: usize,
     terrain: Vec<Terrain>,
     hives: Vec<Hive>,
+    score: Score,
+    high_scores: HighScores,
+    endless_scores: HighScores,
+    score_attack_scores: HighScores,
+    /// Seconds into the run each time attack stage was cleared at.
+    splits: Vec<f32>,
+    best_splits: BestSplits,
+    stats: RunStats,
+    achievements: Achievements,
+    daily: DailyChallenge,
+    /// Whether there's a run to go back to from the main menu.
+    run_in_progress: bool,
+    checkpoint: Option<Checkpoint>,
+    particles: ParticleSystem,
+    juice: Juice,
+    adaptive: AdaptiveDifficulty,
+    settings: Settings,
+    audio: Audio,
+    events: EventQueue,
 }
 
+impl World {
+    pub fn new(mut audio: Audio) -> Self {
+        let bounds = Rect::new(0.0, 0.0, ARENA_WIDTH, ARENA_HEIGHT);
+        let settings = Settings::load();
+        audio.set_mixer(settings.mixer);
+
+        World {
+            mode: Default::default(),
+            bounds,
+            camera: FollowCamera::new(bounds),
+            players: vec![Player::at(bounds.center())],
+            seed: 0,
+            layout_rng: Rng::new(0),
+            spawn_rng: Rng::new(0),
+            stage: 0,
+            hives_saved: 0,
+            honey: 0,
+            projectiles: Vec::new(),
+            enemies: Vec::new(),
+            dying: Vec::new(),
+            enemies_remaining: 0,
+            terrain: Vec::new(),
+            hives: Vec::new(),
+            score: Default::default(),
+            high_scores: HighScores::load(),
+            endless_scores: HighScores::load_endless(),
+            score_attack_scores: HighScores::load_score_attack(),
+            splits: Vec::new(),
+            best_splits: BestSplits::load(),
+            stats: Default::default(),
+            achievements: Achievements::load(),
+            daily: DailyChallenge::load(),
+            run_in_progress: false,
+            checkpoint: None,
+            particles: Default::default(),
+            juice: Juice::with_level(settings.effects),
+            adaptive: AdaptiveDifficulty::new(settings.adaptive_difficulty),
+            settings,
+            audio,
+            events: Default::default(),
+        }
+    }
+}
+
+/// The arena with the HUD over it, leaving the HUD camera set for whatever's
+/// drawn on top.
 impl Drawable for World {
     fn draw(&self) {
-        clear_background(BLUE);
+        self.draw_arena();
+        self.draw_hud();
+    }
+}
 
-        for enemy in &self.enemies {
-            enemy.draw();
-        }
+impl World {
+    pub fn mode(&self) -> GameMode {
+        self.mode
+    }
 
-        for t in &self.terrain {
-            t.draw();
-        }
+    pub fn settings(&self) -> &Settings {
+        &self.settings
+    }
 
-        for h in &self.hives {
-            h.draw();
-        }
+    pub fn score(&self) -> &Score {
+        &self.score
+    }
 
-        self.player.draw();
+    pub fn stats(&self) -> &RunStats {
+        &self.stats
+    }
 
-        for projectile in &self.projectiles {
-            projectile.draw();
+    /// Endless and score attack runs have tables of their own; time attack
+    /// runs are timed rather than scored, so have none.
+    pub fn high_scores(&self, mode: GameMode) -> Option<&HighScores> {
+        match mode {
+            GameMode::Endless => Some(&self.endless_scores),
+            GameMode::ScoreAttack => Some(&self.score_attack_scores),
+            GameMode::TimeAttack => None,
+            _ => Some(&self.high_scores),
         }
+    }
 
-        if self.state != GameState::Game {
-            draw_rectangle(
-                0.0,
-                0.0,
-                screen_width(),
-                screen_height(),
-                Color::new(0.0, 0.0, 0.0, 0.5),
-            );
-        }
+    pub fn splits(&self) -> &[f32] {
+        &self.splits
+    }
 
-        if self.state == GameState::Victory {
-            draw_centered_text(
-                &format!("You saved {} hives!", self.hives.len()),
-                screen_width() / 2.0,
-                screen_height() / 2.0,
-                100,
-                WHITE,
-            );
+    pub fn best_splits(&self) -> &BestSplits {
+        &self.best_splits
+    }
 
-            draw_centered_text(
-                "Press ENTER to progress",
-                screen_width() / 2.0,
-                screen_height() / 2.0 + 100.0,
-                50,
-                WHITE,
-            );
-        } else if self.state == GameState::Pregame {
-            draw_centered_text(
-                "Hornet Invaders",
-                screen_width() / 2.0,
-                screen_height() / 2.0,
-                100,
-                WHITE,
-            );
-            draw_centered_text(
-                "Press ENTER to begin",
-                screen_width() / 2.0,
-                screen_height() / 2.0 + 100.0,
-                50,
-                WHITE,
-            );
-        } else if self.state == GameState::Defeat {
-            draw_centered_text(
-                "GAME OVER",
-                screen_width() / 2.0,
-                screen_height() / 2.0,
-                100,
-                RED,
-            );
-            draw_centered_text(
-                "Press ENTER to try again",
-                screen_width() / 2.0,
-                screen_height() / 2.0 + 100.0,
-                50,
-                WHITE,
-            );
-        } else if self.state == GameState::Pause {
-            draw_centered_text(
-                "PAUSED",
-                screen_width() / 2.0,
-                screen_height() / 2.0,
-                100,
-                WHITE,
-            );
-        }
+    /// Keeps the run's splits if they're the best yet, returning whether they
+    /// were.
+    pub fn record_splits(&mut self) -> bool {
+        self.best_splits.record(&self.splits)
+    }
 
-        self.draw_hud();
+    pub fn achievements(&self) -> &Achievements {
+        &self.achievements
+    }
+
+    pub fn player_count(&self) -> usize {
+        self.players.len()
+    }
+
+    pub fn honey(&self) -> u32 {
+        self.honey
+    }
+
+    pub fn hives_left(&self) -> usize {
+        self.hives.len()
+    }
+
+    pub fn daily(&self) -> &DailyChallenge {
+        &self.daily
+    }
+
+    pub fn run_in_progress(&self) -> bool {
+        self.run_in_progress
+    }
+
+    pub fn start(&mut self, mode: GameMode) {
+        self.mode = mode;
+        self.reset();
     }
-}
 
-impl World {
     pub fn reset(&mut self) {
+        // one daily attempt, however the run gets started again
+        if self.mode == GameMode::Daily && self.daily.attempted_today() {
+            self.mode = GameMode::Classic;
+        }
+        match self.mode {
+            GameMode::Daily => {
+                self.seed = daily_seed(&today());
+                self.daily.start();
+            }
+            GameMode::TimeAttack => self.seed = TIME_ATTACK_SEED,
+            _ => self.seed = (date::now() * 1000.0) as u64,
+        }
+        rand::srand(self.seed);
+        self.adaptive.enabled = self.adaptive_enabled();
+        self.run_in_progress = true;
         self.stage = 0;
         self.hives_saved = 0;
+        self.score.reset();
+        self.stats.reset();
+        self.honey = 0;
+        self.hives.clear();
+        self.splits.clear();
+        // competitive runs are compared with each other, so they're solo
+        let players = if self.mode.is_competitive() {
+            1
+        } else {
+            self.settings.players
+        };
+        self.players = (0..players).map(|_| Player::default()).collect();
         self.set_stage();
     }
 
+    fn take_checkpoint(&mut self) {
+        self.checkpoint = Some(Checkpoint {
+            score: self.score.total,
+            best_combo: self.score.best_combo,
+            honey: self.honey,
+            hives: self.hives.clone(),
+            stats: self.stats.clone(),
+        });
+    }
+
+    /// Competitive modes are played at Normal, so runs can be compared.
+    fn tuning(&self) -> Tuning {
+        if self.mode.is_competitive() {
+            Default::default()
+        } else {
+            self.settings.tuning()
+        }
+    }
+
+    fn adaptive_enabled(&self) -> bool {
+        self.settings.adaptive_difficulty && !self.mode.is_competitive()
+    }
+
+    /// Seeds the stage's generators from the run's seed and the stage alone,
+    /// so a stage plays out the same however the ones before it went.
+    fn seed_stage(&mut self) {
+        let seed = self.seed.wrapping_add(self.stage as u64 * 2);
+        self.layout_rng = Rng::new(seed);
+        self.spawn_rng = Rng::new(seed.wrapping_add(1));
+    }
+
     pub fn set_stage(&mut self) {
-        self.terrain = (0..rand::gen_range(3, 10 + self.stage))
-            .map(|_| {
-                let mut f = Terrain::default();
-                f.kind = TerrainKind::Flower;
-                f
+        self.take_checkpoint();
+        self.seed_stage();
+
+        let bounds = self.bounds;
+        // scale the amount of scenery with how many screens the arena covers
+        let screens = ((bounds.w * bounds.h) / (VIRTUAL_WIDTH * VIRTUAL_HEIGHT)).max(1.0);
+        let flowers = (3 + self.layout_rng.below(7 + self.stage)) as f32 * screens;
+        if !self.mode.uses_honey() || self.hives.is_empty() {
+            let hp = self.tuning().hive_hp;
+            let rng = &mut self.layout_rng;
+            self.hives = (0..STARTING_HIVES)
+                .map(|_| Hive::random(bounds, hp, rng))
+                .collect();
+        }
+        // hives carried over or built in the shop were placed against the
+        // last stage's flowers, so the new ones grow around them
+        let (hives, rng) = (&self.hives, &mut self.layout_rng);
+        self.terrain = (0..flowers as usize)
+            .filter_map(|_| {
+                (0..PLACEMENT_ATTEMPTS)
+                    .map(|_| Terrain::random(TerrainKind::Flower, bounds, rng))
+                    .find(|flower| !hives.iter().any(|h| h.collides_with(flower)))
             })
             .collect();
-        self.hives = (0..3).map(|_| Hive::default()).collect();
-        self.enemies_remaining = (self.stage + 1) * 10;
-        self.state = GameState::Game;
-        self.player = Player::default();
+        self.enemies_remaining = self.stage_size();
+        self.place_players();
+        self.clear_arena();
+        self.events
+            .push(GameEvent::StageStarted { stage: self.stage });
+    }
+
+    /// Puts every bee back at the middle of the arena with full HP.
+    fn place_players(&mut self) {
+        let bounds = self.bounds;
+        let count = self.players.len();
+        let max_hp = self.tuning().player_hp;
+        self.players = (0..count)
+            .map(|i| {
+                // side by side, either side of the middle
+                let offset = (i as f32 - (count - 1) as f32 / 2.0) * PLAYER_SPACING;
+                let mut player = Player::at(bounds.center() + Vec2::new(offset, 0.0));
+                player.max_hp = max_hp;
+                player.hp = max_hp;
+                player.color = PLAYER_COLORS[i];
+                player
+            })
+            .collect();
+        self.camera.snap_to(self.players_center());
+    }
+
+    /// Clears away everything in flight, leaving the flowers and hives.
+    fn clear_arena(&mut self) {
         self.enemies.clear();
+        self.dying.clear();
         self.projectiles.clear();
+        self.particles.clear();
+        self.juice.clear();
+    }
+
+    /// How many hornets the stage sends in all.
+    fn stage_size(&self) -> usize {
+        ((self.stage + 1) as f32 * 10.0 * self.tuning().count) as usize
     }
 
     pub fn max_enemies(&self) -> usize {
-        (self.stage + 1) * 5
+        let count = self.tuning().count * self.adaptive.spawn_factor();
+        ((self.stage + 1) as f32 * 5.0 * count).ceil() as usize
     }
 
     pub fn stage_speed(&self) -> f32 {
-        self.stage as f32 * 0.5 + 1.0
+        (self.stage as f32 * 0.5 + 1.0) * self.tuning().speed * self.adaptive.speed_factor()
     }
 
-    pub fn tick(&mut self) {
-        match self.state {
-            GameState::Pregame | GameState::Defeat => {
-                if is_key_pressed(KeyCode::Enter) {
-                    self.reset();
-                }
+    /// Feeds the frame's events to everything that reacts to them, and sets
+    /// the music for whichever scene is showing. The hornet drone is only
+    /// heard during `gameplay`.
+    pub fn end_frame(&mut self, music: MusicTrack, gameplay: bool) {
+        for event in self.events.drain() {
+            if let Some(sound) = event.sound() {
+                self.audio.play(sound);
             }
-            GameState::Victory => {
-                if is_key_pressed(KeyCode::Enter) {
-                    self.stage += 1;
-                    self.hives_saved += self.hives.len();
-                    self.set_stage();
+            self.score.handle(&event);
+            self.stats.handle(&event);
+            self.achievements.handle(&event);
+            self.particles.handle(&event);
+            self.juice.handle(&event);
+            self.adaptive.handle(&event);
+            if let GameEvent::GameOver { stage } = event {
+                if self.mode == GameMode::Daily {
+                    self.daily.finish(self.score.total, stage + 1);
                 }
             }
-            GameState::Pause => {
-                if is_key_pressed(KeyCode::Escape) {
-                    self.state = GameState::Game;
-                }
-            }
-            GameState::Game => {
-                self.handle_input();
-                self.player.tick();
+        }
+        self.achievements.check(&self.stats, &self.score);
+        self.achievements.tick(get_frame_time());
+        self.audio.play_music(music);
+
+        let players = &self.players;
+        let nearest_enemy = if gameplay {
+            self.enemies
+                .iter()
+                .flat_map(|enemy| {
+                    let pos = enemy.bounding_box().center();
+                    players.iter().map(move |player| pos.distance(player.pos()))
+                })
+                .min_by(|a, b| a.partial_cmp(b).unwrap())
+        } else {
+            None
+        };
+        self.audio.set_drone_distance(nearest_enemy);
+    }
 
-                if self.enemies.len() < self.max_enemies() && self.enemies_remaining > 0 {
-                    self.enemies.push(Enemy::with_speed(self.stage_speed()));
-                    self.enemies_remaining -= 1;
-                }
+    /// Advances play by a frame, returning how the stage ended if it did.
+    pub fn step(&mut self) -> Option<StageOutcome> {
+        self.juice.tick(get_frame_time());
+        if self.juice.is_frozen() {
+            return None;
+        }
 
-                for projectile in &mut self.projectiles {
-                    projectile.tick();
+        self.handle_input();
+        for player in &mut self.players {
+            player.tick();
+        }
 
-                    for enemy in &mut self.enemies {
-                        if enemy.collides_with(projectile) {
-                            enemy.hp -= projectile.damage as i32;
-                            projectile.active = false;
-                        }
-                    }
+        if self.enemies.len() < self.max_enemies() && self.enemies_remaining > 0 {
+            let kind = EnemyKind::random(self.stage, &mut self.spawn_rng);
+            let speed = self.stage_speed();
+            self.enemies
+                .push(Enemy::new(kind, speed, self.bounds, &mut self.spawn_rng));
+            self.enemies_remaining -= 1;
+        }
 
-                    for terrain in &mut self.terrain {
-                        if projectile.collides_with(terrain) {
-                            projectile.active = false;
-                        }
+        self.particles.tick(get_frame_time());
+        self.score.tick(get_frame_time());
+        self.stats.tick(get_frame_time());
+        self.adaptive.tick(get_frame_time());
+
+        for projectile in &mut self.projectiles {
+            projectile.tick();
+
+            // a stinger stops in the first live hornet it meets, so each one
+            // hits once and each hornet dies once
+            for enemy in &mut self.enemies {
+                if enemy.hp > 0 && enemy.collides_with(projectile) {
+                    enemy.hp -= projectile.damage as i32;
+                    enemy.flash = self.juice.flash_secs();
+                    projectile.active = false;
+                    self.events.push(GameEvent::EnemyHit {
+                        pos: projectile.pos(),
+                        direction: projectile.direction,
+                        damage: projectile.damage as i32,
+                    });
+
+                    if enemy.hp <= 0 {
+                        self.events.push(GameEvent::EnemyKilled {
+                            pos: enemy.bounding_box().center(),
+                            kind: enemy.kind,
+                        });
                     }
+                    break;
+                }
+            }
 
-                    if projectile.fully_offscreen() {
-                        projectile.active = false;
-                    }
+            for terrain in &mut self.terrain {
+                if projectile.active && projectile.collides_with(terrain) {
+                    projectile.active = false;
+                    self.events.push(GameEvent::ShotBlocked {
+                        pos: projectile.pos(),
+                        direction: projectile.direction,
+                    });
                 }
+            }
 
-                for enemy in &mut self.enemies {
-                    let mut desired_movement = enemy.desired_movement(&self.hives);
-                    for t in &mut self.terrain {
-                        desired_movement = enemy.handle_collision(desired_movement, t);
-                    }
-                    enemy.move_by(desired_movement);
-                    enemy.tick();
-
-                    for hive in &mut self.hives {
-                        if enemy.collides_with(hive) {
-                            hive.hp -= 1;
-                            enemy.hp = 0;
-                            break;
-                        }
+            if projectile.fully_outside(self.bounds) {
+                projectile.active = false;
+            }
+        }
+
+        let damage = self.tuning().damage;
+        for enemy in &mut self.enemies {
+            let mut desired_movement = enemy.desired_movement(&self.hives);
+            for t in &mut self.terrain {
+                desired_movement = enemy.handle_collision(desired_movement, t);
+            }
+            enemy.move_by(desired_movement);
+            enemy.tick();
+
+            for hive in &mut self.hives {
+                if enemy.collides_with(hive) {
+                    let lost = damage.min(hive.hp).max(0);
+                    hive.damage(damage);
+                    hive.flash = self.juice.flash_secs();
+                    enemy.hp = 0;
+
+                    let pos = hive.bounding_box().center();
+                    self.events.push(GameEvent::EnemyStung {
+                        pos: enemy.bounding_box().center(),
+                    });
+                    self.events.push(GameEvent::HiveDamaged {
+                        pos,
+                        hp: hive.hp,
+                        lost,
+                    });
+                    if hive.hp <= 0 {
+                        self.events.push(GameEvent::HiveDestroyed { pos });
                     }
+                    break;
+                }
+            }
 
-                    if self.player.state == PlayerState::Ok {
-                        if enemy.collides_with(&self.player) {
-                            self.player.hp -= 1;
-                            self.player.state = PlayerState::Invulnerable(get_time() + 1.0);
-                        }
+            for player in &mut self.players {
+                if player.state == PlayerState::Ok && enemy.collides_with(&*player) {
+                    let lost = damage.min(player.hp);
+                    let downed = player.sting(damage);
+                    self.events.push(GameEvent::PlayerDamaged {
+                        hp: player.hp,
+                        lost,
+                    });
+                    if downed {
+                        self.events
+                            .push(GameEvent::PlayerDowned { pos: player.pos() });
                     }
                 }
+            }
+        }
+        self.revive_players();
 
-                self.projectiles.retain(|projectile| projectile.active);
-                self.enemies.retain(|enemy| enemy.hp > 0);
-                self.hives.retain(|hive| hive.hp > 0);
+        for hive in &mut self.hives {
+            hive.tick();
 
-                if self.player.hp <= 0 || self.hives.is_empty() {
-                    self.state = GameState::Defeat;
-                    return;
-                }
+            let damage = 1.0 - hive.hp as f32 / hive.max_hp as f32;
+            if rand::gen_range(0.0, 1.0) < damage * 0.2 * frame_steps() {
+                let bb = hive.bounding_box();
+                let pos = Vec2::new(rand::gen_range(bb.left(), bb.right()), bb.bottom());
+                self.particles.emit(&HONEY_DRIP, pos, FRAC_PI_2);
+            }
+        }
 
-                if self.enemies.is_empty() {
-                    self.state = GameState::Victory;
-                    return;
+        for enemy in &mut self.dying {
+            enemy.tick();
+        }
+        self.dying.retain(|enemy| !enemy.body.finished());
+
+        self.projectiles.retain(|projectile| projectile.active);
+        let (alive, dead): (Vec<_>, Vec<_>) =
+            self.enemies.drain(..).partition(|enemy| enemy.hp > 0);
+        self.enemies = alive;
+        self.dying.extend(dead.into_iter().map(|mut enemy| {
+            enemy.die();
+            enemy
+        }));
+        self.hives.retain(|hive| hive.hp > 0);
+
+        if self.players.iter().all(Player::is_down) || self.hives.is_empty() {
+            self.events.push(GameEvent::GameOver { stage: self.stage });
+            self.run_in_progress = false;
+            return Some(StageOutcome::Lost);
+        }
+
+        self.stage_outcome()
+    }
+
+    /// The mode's own ways for a stage to end, short of losing. Modes that
+    /// don't stop between stages move straight on when one is cleared.
+    fn stage_outcome(&mut self) -> Option<StageOutcome> {
+        if self.mode == GameMode::ScoreAttack && self.stats.time_survived >= SCORE_ATTACK_SECS {
+            return Some(self.finish_run());
+        }
+        if !self.enemies.is_empty() || self.enemies_remaining > 0 {
+            return None;
+        }
+
+        if self.mode.uses_honey() {
+            self.honey += self.hives.iter().map(|hive| hive.hp as u32).sum::<u32>();
+        }
+        self.events.push(GameEvent::StageCleared {
+            stage: self.stage,
+            full_hives: self.hives.iter().filter(|h| h.hp == h.max_hp).count(),
+        });
+
+        match self.mode {
+            GameMode::Endless => {
+                self.next_wave();
+                None
+            }
+            GameMode::TimeAttack => {
+                self.splits.push(self.stats.time_survived);
+                if self.splits.len() == TIME_ATTACK_STAGES {
+                    Some(self.finish_run())
+                } else {
+                    self.next_stage();
+                    None
                 }
             }
+            GameMode::ScoreAttack => {
+                self.next_stage();
+                None
+            }
+            _ => Some(StageOutcome::Cleared),
         }
     }
 
-    fn handle_input(&mut self) {
-        if is_key_pressed(KeyCode::Escape) {
-            self.state = GameState::Pause;
+    fn finish_run(&mut self) -> StageOutcome {
+        self.events
+            .push(GameEvent::RunFinished { stage: self.stage });
+        self.run_in_progress = false;
+        StageOutcome::Finished
+    }
+
+    /// Downed bees come back once another bee has stayed beside them for
+    /// long enough.
+    fn revive_players(&mut self) {
+        for i in 0..self.players.len() {
+            if !self.players[i].is_down() {
+                continue;
+            }
+
+            let pos = self.players[i].pos();
+            let helped = self
+                .players
+                .iter()
+                .any(|player| !player.is_down() && player.pos().distance(pos) <= REVIVE_RADIUS);
+            if helped && self.players[i].revive(get_frame_time()) {
+                self.events.push(GameEvent::PlayerRevived { pos });
+            }
+        }
+    }
+
+    /// Where the camera looks: between all the bees, downed ones included.
+    fn players_center(&self) -> Vec2 {
+        let sum = self
+            .players
+            .iter()
+            .fold(Vec2::ZERO, |sum, player| sum + player.pos());
+        sum / self.players.len() as f32
+    }
+
+    /// How far the bee at index `i` can stray, so the camera between the bees
+    /// keeps every one of them on screen; `None` when it's on its own.
+    fn tether(&self, i: usize) -> Option<Rect> {
+        let reach = Vec2::new(VIRTUAL_WIDTH, VIRTUAL_HEIGHT) - Vec2::splat(TETHER_MARGIN * 2.0);
+        let mut others = self
+            .players
+            .iter()
+            .enumerate()
+            .filter(|&(j, _)| j != i)
+            .map(|(_, player)| player.pos());
+        let first = others.next()?;
+        let (min, max) = others.fold((first - reach, first + reach), |(min, max), pos| {
+            (min.max(pos - reach), max.min(pos + reach))
+        });
+
+        Some(Rect::new(min.x, min.y, max.x - min.x, max.y - min.y))
+    }
+
+    pub fn next_stage(&mut self) {
+        self.stage += 1;
+        self.hives_saved += self.hives.len();
+        self.set_stage();
+    }
+
+    /// Sends the next, bigger wave straight in, leaving the arena as it is.
+    fn next_wave(&mut self) {
+        self.stage += 1;
+        self.start_wave();
+    }
+
+    /// Sends in the current wave's hornets, seeded and checkpointed.
+    fn start_wave(&mut self) {
+        self.take_checkpoint();
+        self.seed_stage();
+        self.enemies_remaining = self.stage_size();
+        self.events
+            .push(GameEvent::StageStarted { stage: self.stage });
+    }
+
+    /// Spends honey on a new hive clear of the others and the flowers. Nothing
+    /// is spent if there's no room for one.
+    pub fn found_hive(&mut self) {
+        if self.honey < HIVE_COST {
             return;
         }
 
-        let mut player_movement = self.player.handle_input();
+        let (bounds, hp) = (self.bounds, self.tuning().hive_hp);
+        let (hives, terrain) = (&self.hives, &self.terrain);
+        let rng = &mut self.layout_rng;
+        let hive = (0..PLACEMENT_ATTEMPTS)
+            .map(|_| Hive::random(bounds, hp, rng))
+            .find(|hive| {
+                !hives.iter().any(|h| h.collides_with(hive))
+                    && !terrain.iter().any(|t| t.collides_with(hive))
+            });
+        let hive = match hive {
+            Some(hive) => hive,
+            None => return,
+        };
+
+        self.honey -= HIVE_COST;
+        self.hives.push(hive);
+    }
+
+    /// Puts the score, hives and stats back how they were when the stage
+    /// started, then starts it over. Endless waves share one arena, so only
+    /// the wave starts over there.
+    pub fn restart_stage(&mut self) {
+        if let Some(checkpoint) = self.checkpoint.take() {
+            self.score.reset();
+            self.score.total = checkpoint.score;
+            self.score.best_combo = checkpoint.best_combo;
+            self.honey = checkpoint.honey;
+            self.hives = checkpoint.hives;
+            self.stats = checkpoint.stats;
+        }
+        if self.mode == GameMode::Endless {
+            self.place_players();
+            self.clear_arena();
+            self.start_wave();
+        } else {
+            self.set_stage();
+        }
+    }
+
+    /// The finished run, as it would go in the high-score table.
+    pub fn run_record(&self) -> HighScore {
+        let hives = if self.mode == GameMode::Endless {
+            self.hives.len()
+        } else {
+            self.hives_saved
+        };
+        HighScore::new(self.score.total, self.stage + 1, hives, self.seed)
+    }
+
+    /// Returns where the record placed.
+    pub fn record_high_score(&mut self, record: HighScore) -> Option<usize> {
+        let high_scores = match self.mode {
+            GameMode::Endless => &mut self.endless_scores,
+            GameMode::ScoreAttack => &mut self.score_attack_scores,
+            GameMode::TimeAttack => return None,
+            _ => &mut self.high_scores,
+        };
+        let placement = high_scores.insert(record);
+        high_scores.save();
+        placement
+    }
+
+    pub fn apply_settings(&mut self, settings: Settings) {
+        if settings.fullscreen != self.settings.fullscreen {
+            set_fullscreen(settings.fullscreen);
+        }
+        if settings.window_size != self.settings.window_size && !settings.fullscreen {
+            request_new_screen_size(settings.window_size.0 as f32, settings.window_size.1 as f32);
+        }
+
+        if settings != self.settings {
+            self.settings = settings;
+            self.settings.save();
+            self.juice.level = settings.effects;
+            self.adaptive.enabled = self.adaptive_enabled();
+            self.audio.set_mixer(settings.mixer);
+        }
+    }
+
+    fn handle_input(&mut self) {
+        let controls = self.settings.controls;
+        if self.mode == GameMode::Endless && controls.is_pressed(Action::BuildHive) {
+            self.found_hive();
+        }
+
+        if controls.is_pressed(Action::Minimap) {
+            let mut settings = self.settings;
+            settings.show_minimap = !settings.show_minimap;
+            self.apply_settings(settings);
+        }
+
+        for i in 0..self.players.len() {
+            if !self.players[i].is_down() {
+                self.handle_player_input(i);
+            }
+        }
+        self.camera.follow(self.players_center());
+    }
+
+    /// Moves and shoots for the bee at index `i`, from its own keys.
+    fn handle_player_input(&mut self, i: usize) {
+        let controls = self.settings.player_controls(i);
+        let tether = self.tether(i);
+        let player = &mut self.players[i];
+        let mut player_movement = player.handle_input(&controls);
 
         for t in &self.terrain {
-            player_movement = self.player.handle_collision(player_movement, t);
+            player_movement = player.handle_collision(player_movement, t);
+        }
+
+        player.move_by(player_movement);
+        player.constrain(self.bounds);
+        if let Some(tether) = tether {
+            player.constrain(tether);
         }
 
-        self.player.move_by(player_movement);
-        self.player.screen_constrain();
+        if player_movement.length() > 0.0 {
+            let behind = player_movement.y.atan2(player_movement.x) + PI;
+            self.particles.emit(&POLLEN_TRAIL, player.pos(), behind);
+        }
 
-        if self.projectiles.len() < self.player.max_projectiles {
-            if is_key_pressed(KeyCode::Up) {
-                self.projectiles.push(self.player.shoot(Direction::Up));
+        let hold_to_fire = self.settings.hold_to_fire;
+        let fire = |action| {
+            if hold_to_fire {
+                controls.is_down(action)
+            } else {
+                controls.is_pressed(action)
             }
-            if is_key_pressed(KeyCode::Down) {
-                self.projectiles.push(self.player.shoot(Direction::Down));
+        };
+        let shots = self.projectiles.iter().filter(|p| p.owner == i).count();
+        if shots < self.players[i].max_projectiles {
+            if fire(Action::ShootUp) {
+                self.shoot(i, Direction::Up);
             }
-            if is_key_pressed(KeyCode::Left) {
-                self.projectiles.push(self.player.shoot(Direction::Left));
+            if fire(Action::ShootDown) {
+                self.shoot(i, Direction::Down);
             }
-            if is_key_pressed(KeyCode::Right) {
-                self.projectiles.push(self.player.shoot(Direction::Right));
+            if fire(Action::ShootLeft) {
+                self.shoot(i, Direction::Left);
+            }
+            if fire(Action::ShootRight) {
+                self.shoot(i, Direction::Right);
             }
         }
     }
 
-    fn draw_hud(&self) {
-        self.player.draw_hp();
-        draw_text(
-            &format!("Hives saved: {}", self.hives_saved),
-            20.0,
-            50.0,
-            50.0,
-            LIGHTGRAY,
+    fn shoot(&mut self, i: usize, direction: Direction) {
+        let player = &self.players[i];
+        self.projectiles.push(player.shoot(direction, i));
+        self.events.push(GameEvent::Shot {
+            pos: player.pos(),
+            direction,
+        });
+    }
+
+    /// Everything in world space, without the HUD.
+    pub fn draw_arena(&self) {
+        clear_background(BLACK);
+
+        let mut camera = self.camera.camera();
+        self.juice.shake(&mut camera);
+        set_camera(&camera);
+
+        let view = self.camera.view_rect();
+        draw_rectangle(view.x, view.y, view.w, view.h, DARKBLUE);
+
+        draw_rectangle(
+            self.bounds.x,
+            self.bounds.y,
+            self.bounds.w,
+            self.bounds.h,
+            BLUE,
         );
-        draw_h_centered_text(
-            &format!("Stage: {}", self.stage + 1),
-            screen_width() / 2.0,
-            50.0,
-            50,
-            LIGHTGRAY,
+
+        for enemy in self.dying.iter().chain(&self.enemies) {
+            enemy.draw();
+        }
+
+        for t in &self.terrain {
+            t.draw();
+        }
+
+        for h in &self.hives {
+            h.draw();
+        }
+
+        for player in &self.players {
+            match player.state {
+                PlayerState::Invulnerable(_) if self.juice.blinks() => {
+                    if self.juice.blink_visible() {
+                        player.draw_body(player.color);
+                    }
+                }
+                _ => player.draw(),
+            }
+        }
+
+        for projectile in &self.projectiles {
+            projectile.draw();
+        }
+
+        self.particles.draw();
+        self.score.draw();
+
+        set_camera(&hud_camera());
+    }
+
+    fn draw_hud(&self) {
+        if let [player] = &self.players[..] {
+            player.draw_hp("", 50.0, LIGHTGRAY);
+        } else {
+            for (i, player) in self.players.iter().enumerate() {
+                let label = format!("P{} ", i + 1);
+                player.draw_hp(&label, 50.0 + 50.0 * i as f32, player.color);
+            }
+        }
+        let endless = self.mode == GameMode::Endless;
+        let hives = if endless {
+            format!("Hives: {}", self.hives.len())
+        } else {
+            format!("Hives saved: {}", self.hives_saved)
+        };
+        draw_scaled_text(&hives, 20.0, 50.0, 50, LIGHTGRAY);
+        if self.mode.uses_honey() {
+            draw_scaled_text(
+                &format!("Honey: {}", self.honey),
+                20.0,
+                100.0,
+                50,
+                LIGHTGRAY,
+            );
+        }
+        if endless {
+            draw_scaled_text(
+                &format!(
+                    "{} builds a hive ({} honey)",
+                    key_name(self.settings.controls.key(Action::BuildHive)),
+                    HIVE_COST
+                ),
+                20.0,
+                140.0,
+                30,
+                if self.honey >= HIVE_COST { WHITE } else { GRAY },
+            );
+        }
+        match self.mode {
+            GameMode::TimeAttack => draw_scaled_text(
+                &format!("Time: {}", format_time(self.stats.time_survived)),
+                20.0,
+                100.0,
+                50,
+                LIGHTGRAY,
+            ),
+            GameMode::ScoreAttack => {
+                let left = SCORE_ATTACK_SECS - self.stats.time_survived;
+                draw_scaled_text(
+                    &format!("Time left: {}", format_time(left)),
+                    20.0,
+                    100.0,
+                    50,
+                    if left < 10.0 { RED } else { LIGHTGRAY },
+                );
+            }
+            _ => {}
+        }
+        let stage = if self.mode == GameMode::TimeAttack {
+            format!("Stage: {}/{}", self.stage + 1, TIME_ATTACK_STAGES)
+        } else {
+            format!(
+                "{}: {}",
+                if endless { "Wave" } else { "Stage" },
+                self.stage + 1
+            )
+        };
+        draw_h_centered_text(&stage, VIRTUAL_WIDTH / 2.0, 50.0, 50, LIGHTGRAY);
+        if self.settings.show_fps {
+            draw_scaled_text(&get_fps().to_string(), 20.0, 20.0, 30, DARKGRAY);
+        }
+
+        self.score.draw_hud();
+
+        if self.settings.show_minimap {
+            self.draw_minimap();
+        }
+    }
+
+    fn draw_minimap(&self) {
+        let scale = MINIMAP_WIDTH / self.bounds.w;
+        let map = Rect::new(
+            VIRTUAL_WIDTH - MINIMAP_MARGIN - MINIMAP_WIDTH,
+            VIRTUAL_HEIGHT - MINIMAP_MARGIN - self.bounds.h * scale,
+            MINIMAP_WIDTH,
+            self.bounds.h * scale,
         );
-        draw_text(&get_fps().to_string(), 20.0, 20.0, 30.0, DARKGRAY);
+
+        // approaching hornets are pinned to the edge of the map
+        let to_map = |pos: Vec2| {
+            Vec2::new(
+                (map.x + (pos.x - self.bounds.x) * scale).clamp(map.left(), map.right()),
+                (map.y + (pos.y - self.bounds.y) * scale).clamp(map.top(), map.bottom()),
+            )
+        };
+        let to_map_rect = |rect: Rect| {
+            let pos = to_map(rect.point());
+            Rect::new(pos.x, pos.y, rect.w * scale, rect.h * scale)
+        };
+
+        draw_rectangle(map.x, map.y, map.w, map.h, Color::new(0.0, 0.0, 0.3, 0.6));
+
+        for t in &self.terrain {
+            let r = to_map_rect(t.bounding_box());
+            draw_rectangle(r.x, r.y, r.w, r.h, DARKGREEN);
+        }
+
+        for h in &self.hives {
+            let r = to_map_rect(h.bounding_box());
+            draw_rectangle(r.x, r.y, r.w.max(4.0), r.h.max(4.0), h.color());
+        }
+
+        for enemy in &self.enemies {
+            let pos = to_map(enemy.bounding_box().center());
+            draw_circle(pos.x, pos.y, 3.0, RED);
+        }
+
+        for player in &self.players {
+            let pos = to_map(player.pos());
+            draw_circle(pos.x, pos.y, 4.0, player.color);
+        }
+
+        let view = to_map_rect(self.camera.view_rect());
+        draw_rectangle_lines(view.x, view.y, view.w, view.h, 2.0, WHITE);
+        draw_rectangle_lines(map.x, map.y, map.w, map.h, 2.0, LIGHTGRAY);
     }
 }